@@ -1,80 +1,25 @@
-use aoc2025::get_input_as_str;
-use atoi::atoi;
+use aoc2025::parse::extract_ints;
+use aoc2025::ranges::RangeIndex;
+use aoc2025::{get_input_as_str, split_sections};
 
 #[inline(always)]
-fn parse_input(input: &str) -> (Vec<(u64, u64)>, Vec<u64>) {
-    let (ranges_str, ingredients_str) = input.split_once("\n\n").unwrap();
+fn parse_input(input: &str) -> (RangeIndex<u64>, Vec<u64>) {
+    let [ranges_str, ingredients_str] = split_sections(input);
 
-    let fresh_ranges: Vec<(u64, u64)> = ranges_str
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|line| {
-            let (a_str, b_str) = line.split_once('-').unwrap();
-            let mut a: u64 = atoi(a_str.as_bytes()).unwrap();
-            let mut b: u64 = atoi(b_str.as_bytes()).unwrap();
-            if a > b {
-                std::mem::swap(&mut a, &mut b);
-            }
-            (a, b)
-        })
-        .collect();
+    let fresh_ranges = RangeIndex::from_range_lines(ranges_str);
 
-    let ingredients: Vec<u64> = ingredients_str
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|line| atoi(line.as_bytes()).unwrap())
-        .collect();
+    let mut ingredients: Vec<u64> = Vec::new();
+    extract_ints(ingredients_str.as_bytes(), &mut ingredients);
 
     (fresh_ranges, ingredients)
 }
 
 #[inline(always)]
 fn day5(input: &str) -> (usize, usize) {
-    let (mut ranges, mut ingredient_ids) = parse_input(input);
-    let num_ingredients = ingredient_ids.len();
+    let (fresh_ranges, ingredient_ids) = parse_input(input);
 
-    ranges.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-    ingredient_ids.sort_unstable();
-
-    let mut part_one = 0usize;
-    let mut part_two = 0usize;
-
-    let mut it = ranges.into_iter();
-    let mut current = it.next().unwrap();
-    let mut i = 0usize;
-    for (start, end) in it {
-        if start <= current.1.saturating_add(1) {
-            if end > current.1 {
-                current.1 = end;
-            }
-        } else {
-            let (lo, hi) = current;
-            part_two += (hi - lo + 1) as usize;
-
-            while i < num_ingredients && ingredient_ids[i] < lo {
-                i += 1;
-            }
-
-            while i < num_ingredients && ingredient_ids[i] <= hi {
-                part_one += 1;
-                i += 1;
-            }
-
-            current = (start, end);
-        }
-    }
-
-    let (lo, hi) = current;
-    part_two += (hi - lo + 1) as usize;
-
-    while i < num_ingredients && ingredient_ids[i] < lo {
-        i += 1;
-    }
-
-    while i < num_ingredients && ingredient_ids[i] <= hi {
-        part_one += 1;
-        i += 1;
-    }
+    let part_one = fresh_ranges.count_contained(ingredient_ids);
+    let part_two = fresh_ranges.set().len() as usize;
 
     (part_one, part_two)
 }
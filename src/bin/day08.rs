@@ -1,84 +1,59 @@
+use aoc2025::dot::{Dot, dot_output_path};
+use aoc2025::dsu::DisjointSet;
+use aoc2025::geom::Point3D;
 use aoc2025::get_input_as_str;
-use atoi::atoi;
-use rayon::prelude::*;
-use union_find::{QuickFindUf, UnionBySize, UnionFind};
-
-#[derive(Eq, PartialEq)]
-struct Point3D {
-    pub x: i64,
-    pub y: i64,
-    pub z: i64,
-}
+use aoc2025::kdtree::KdTree;
+use aoc2025::mst::euclidean_mst;
+
+#[inline(always)]
+fn day8(input: &str, edge_limit: usize) -> (i64, i64) {
+    let points: Vec<Point3D> = input.lines().map(Point3D::from_csv_str).collect();
+    let num_points = points.len();
 
-impl Point3D {
-    fn from_csv_str(s: &str) -> Point3D {
-        let mut parts = s.split(',');
-        let x = atoi(parts.next().unwrap().as_bytes()).unwrap();
-        let y = atoi(parts.next().unwrap().as_bytes()).unwrap();
-        let z = atoi(parts.next().unwrap().as_bytes()).unwrap();
-        Self { x, y, z }
+    // Part 1: Use only the first 1000 shortest edges, walked lazily in ascending distance.
+    let tree = KdTree::new(&points);
+    let mut circuits = DisjointSet::new(num_points);
+    for (_, i, j) in tree.closest_pairs().take(edge_limit) {
+        circuits.union(i, j);
     }
+
+    let part_one = circuits.top_k_sizes(3).iter().product::<usize>() as i64;
+
+    // Part 2: The edge which finally joins everything into one circuit is the longest edge of the
+    // minimum spanning tree. A single junction box is already one circuit.
+    let part_two = final_connection(&points).map_or(0, |(i, j)| points[i].x * points[j].x);
+
+    (part_one, part_two)
 }
 
-#[inline(always)]
-fn squared_distance(a: &Point3D, b: &Point3D) -> i64 {
-    let dx = a.x - b.x;
-    let dy = a.y - b.y;
-    let dz = a.z - b.z;
-    dx * dx + dy * dy + dz * dz
+/// Returns the pair of junction boxes whose connection completes a single circuit: the heaviest
+/// minimum spanning tree edge, breaking ties by index as Kruskal's algorithm would.
+fn final_connection(points: &[Point3D]) -> Option<(usize, usize)> {
+    euclidean_mst(points)
+        .into_iter()
+        .max_by_key(|&(distance, i, j)| (distance, i, j))
+        .map(|(_, i, j)| (i, j))
 }
 
-#[inline(always)]
-fn day8(input: &str, edge_limit: usize) -> (i64, i64) {
+/// Builds a DOT graph of the junction boxes joined by the first `edge_limit` connections, labelled
+/// with their distances and clustered by circuit, highlighting the pair which completes the circuit.
+fn circuit_dot(input: &str, edge_limit: usize) -> Dot {
     let points: Vec<Point3D> = input.lines().map(Point3D::from_csv_str).collect();
-    let num_points = points.len();
-    let num_edges = num_points * (num_points - 1) / 2;
-
-    // Compute the graph edges and distances and sort by ascending distance.
-    let mut edges: Vec<(i64, usize, usize)> = Vec::with_capacity(num_edges);
-    for i in 0..num_points {
-        for j in (i + 1)..num_points {
-            let point_a = &points[i];
-            let point_b = &points[j];
-            edges.push((squared_distance(point_a, point_b), i, j));
-        }
-    }
-    edges.par_sort_unstable_by_key(|(distance, _, _)| *distance);
-
-    // Part 1: Use only the first 1000 shortest edges.
-    let mut num_circuits = num_points;
-    let mut union_find = QuickFindUf::<UnionBySize>::new(num_points);
-    for &(_, i, j) in &edges[..edge_limit] {
-        if union_find.union(i, j) {
-            num_circuits -= 1;
-        }
-    }
+    let mut dot = Dot::graph(points.iter().map(|p| format!("{},{},{}", p.x, p.y, p.z)));
 
-    let mut circuits = vec![0usize; num_points];
-    for i in 0..num_points {
-        circuits[union_find.find(i)] += 1;
+    let mut circuits = DisjointSet::new(points.len());
+    for (distance, i, j) in KdTree::new(&points).closest_pairs().take(edge_limit) {
+        circuits.union(i, j);
+        dot.labelled_edge(i, j, format!("{:.1}", (distance as f64).sqrt()));
     }
-    circuits.sort_unstable();
-
-    let part_one = circuits
-        .iter()
-        .rev()
-        .take(3)
-        .product::<usize>() as i64;
-
-    // Part 2: Continue until we have a fully connected graph (1 circuit).
-    let mut part_two = 0;
-    for &(_, i, j) in &edges[edge_limit..] {
-        if union_find.union(i, j) {
-            num_circuits -= 1;
-            if num_circuits == 1 {
-                part_two = points[i].x * points[j].x;
-                break;
-            }
-        }
+    for circuit in circuits.components().into_iter().filter(|c| c.len() > 1) {
+        dot.cluster(circuit);
     }
 
-    (part_one, part_two)
+    if let Some((i, j)) = final_connection(&points) {
+        dot.highlight(i).highlight(j);
+    }
+    dot
 }
 
 #[inline(always)]
@@ -86,6 +61,10 @@ fn main() {
     let input = get_input_as_str();
     let (p1, p2) = day8(input, 1000);
     println!("{p1}\n{p2}");
+
+    if dot_output_path().is_some() {
+        circuit_dot(input, 1000).write_if_requested();
+    }
 }
 
 #[cfg(test)]
@@ -123,6 +102,18 @@ mod tests {
         let (part1_answer, part2_answer) = day8(SAMPLE_INPUT, 10);
         assert_eq!(part1_answer, SAMPLE_PART1_ANSWER);
         assert_eq!(part2_answer, SAMPLE_PART2_ANSWER);
+
+        let dot = circuit_dot(SAMPLE_INPUT, 10).to_string();
+        assert_eq!(dot.matches("subgraph cluster_").count(), 4);
+        assert_eq!(dot.matches(" -- ").count(), 10);
+        assert!(dot.contains("[label=\"216,146,977\", style=filled, fillcolor=gold]"));
+        assert!(dot.contains("[label=\"117,168,530\", style=filled, fillcolor=gold]"));
+    }
+
+    #[test]
+    fn test_day8_single_box() {
+        assert_eq!(day8("1,2,3\n", 10).1, 0);
+        assert!(!circuit_dot("1,2,3\n", 10).to_string().contains("gold"));
     }
 
     #[test]
@@ -1,97 +1,31 @@
+use aoc2025::geom::Rect2D;
 use aoc2025::get_input_as_str;
-use atoi::atoi;
+use aoc2025::polygon::Polygon;
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::cmp::Reverse;
 
-#[derive(Eq, PartialEq, Copy, Clone)]
-struct Point2D {
-    pub x: i64,
-    pub y: i64,
-}
-
-impl Point2D {
-    #[inline(always)]
-    fn from_csv_str(s: &str) -> Point2D {
-        let mut parts = s.split(',');
-        let x = atoi(parts.next().unwrap().as_bytes()).unwrap();
-        let y = atoi(parts.next().unwrap().as_bytes()).unwrap();
-        Self { x, y }
-    }
-}
-
-#[derive(Eq, PartialEq, Copy, Clone)]
-struct Rect2D {
-    pub min_x: i64,
-    pub min_y: i64,
-    pub max_x: i64,
-    pub max_y: i64,
-    pub area: i64,
-}
-
-impl Rect2D {
-    #[inline(always)]
-    fn from_corner_pair(corners: (&Point2D, &Point2D)) -> Rect2D {
-        let (p1, p2) = corners;
-        Self::from_corners(p1, p2)
-    }
-
-    #[inline(always)]
-    fn from_corners(p1: &Point2D, p2: &Point2D) -> Rect2D {
-        let min_x = p1.x.min(p2.x);
-        let min_y = p1.y.min(p2.y);
-        let max_x = p1.x.max(p2.x);
-        let max_y = p1.y.max(p2.y);
-        let area = (max_x - min_x + 1) * (max_y - min_y + 1);
-        Rect2D {
-            min_x,
-            min_y,
-            max_x,
-            max_y,
-            area,
-        }
-    }
-
-    #[inline(always)]
-    fn intersects(&self, other: &Rect2D) -> bool {
-        other.max_y > self.min_y
-            && other.min_y < self.max_y
-            && other.max_x > self.min_x
-            && other.min_x < self.max_x
-    }
-}
-
 #[inline(always)]
 fn day9(input: &str) -> (i64, i64) {
-    let points: Vec<Point2D> = input.lines().map(Point2D::from_csv_str).collect();
-    let num_points = points.len();
-
-    // Pre-calculate the edge pairs and 'rotate' the collection so that the edges which bisect the
-    // circle input are near the start of the collection so pairs are quickly canceled out.
-    let edges: Vec<Rect2D> = points
-        .iter()
-        .cycle()
-        .skip(num_points / 2 - 1)
-        .tuple_windows()
-        .take(num_points)
-        .map(Rect2D::from_corner_pair)
-        .collect();
+    let polygon = Polygon::from_csv_lines(input);
+    let tiles = polygon.lattice_index();
 
     // Pre-calculate the edge rectangles and corner pairs and sort by area.
-    let mut rects: Vec<Rect2D> = points
+    let mut rects: Vec<Rect2D> = polygon
+        .vertices()
         .iter()
         .tuple_combinations()
         .map(Rect2D::from_corner_pair)
         .collect();
-    rects.par_sort_unstable_by_key(|r| Reverse(r.area));
+    rects.par_sort_unstable_by_key(|r| Reverse(r.area()));
 
     // Part one: Largest area rect.
-    let part_one = rects[0].area;
+    let part_one = rects[0].area();
 
-    // Part two: Calculate the rects which don't overlap the edges.
+    // Part two: Largest rect which lies entirely within the red and green tiles.
     let part_two = rects
         .iter()
-        .find_map(|r| edges.iter().all(|e| !e.intersects(r)).then_some(r.area))
+        .find_map(|r| tiles.contains_rect(r).then_some(r.area()))
         .unwrap();
 
     (part_one, part_two)
@@ -1,6 +1,38 @@
-use aoc2025::get_input_as_str;
-use good_lp::{Expression, Solution, SolverModel, constraint, microlp, variable, variables};
-use std::collections::VecDeque;
+use aoc2025::gf2::{BitMatrix, BitVector, MinWeightError};
+use aoc2025::ilp::{Infeasibility, Solution};
+use aoc2025::solver::{MinSumSolver, selected_backends, solve_with_fallback};
+use aoc2025::{get_input_as_str, is_verbose, scan};
+use std::fmt::Debug;
+use std::time::Instant;
+
+/// The result of solving one part of a machine.
+#[derive(Debug, Eq, PartialEq)]
+enum Outcome<T> {
+    Solved(T),
+    /// No plan exists, for the given reason.
+    Infeasible(String),
+    /// The solver gave up without finding a plan or proving there is none.
+    Undecided,
+}
+
+impl<T> Outcome<T> {
+    #[inline(always)]
+    fn solved(&self) -> Option<&T> {
+        match self {
+            Outcome::Solved(plan) => Some(plan),
+            _ => None,
+        }
+    }
+
+    /// Describes why there is no plan, if there isn't one.
+    fn problem(&self) -> Option<String> {
+        match self {
+            Outcome::Solved(_) => None,
+            Outcome::Infeasible(reason) => Some(format!("infeasible: {reason}")),
+            Outcome::Undecided => Some("undecided".to_string()),
+        }
+    }
+}
 
 #[derive(Debug, Eq, PartialEq)]
 struct Machine {
@@ -8,29 +40,16 @@ struct Machine {
     buttons: Vec<Vec<usize>>,
     joltage_requirements: Vec<i64>,
 }
-use atoi::atoi;
 
 impl Machine {
     fn from_input_line(line: &str) -> Machine {
-        let (indicator_part, rest) = line.split_once(']').unwrap();
-        let indicators_str = indicator_part.trim_start_matches('[');
+        let (indicators_str, button_strs, joltage_requirements) =
+            scan!(line, "[{}] {} {{{,}}}" => &str, Vec<&str>, Vec<i64>).unwrap();
         let indicator_lights = indicators_str.bytes().map(|b| b == b'#').collect();
 
-        let (buttons_part, jolts_part) = rest.split_once('{').unwrap();
-        let joltage_requirements = jolts_part
-            .trim_end_matches('}')
-            .split(',')
-            .map(|s| atoi::<i64>(s.as_bytes()).unwrap())
-            .collect();
-
-        let buttons = buttons_part
-            .split_whitespace()
-            .map(|part| {
-                part[1..part.len() - 1]
-                    .split(',')
-                    .map(|s| atoi::<usize>(s.as_bytes()).unwrap())
-                    .collect()
-            })
+        let buttons = button_strs
+            .into_iter()
+            .map(|part| scan!(part, "({,})" => Vec<usize>).unwrap().0)
             .collect();
 
         Machine {
@@ -40,89 +59,198 @@ impl Machine {
         }
     }
 
-    fn shortest_press_count_lights(&self) -> i64 {
-        let num_lights = self.indicator_lights.len();
+    /// Returns the fewest buttons which together toggle exactly the lit indicators.
+    fn shortest_press_plan_lights(&self) -> Outcome<Vec<usize>> {
+        // Each light gives an equation over GF(2): the buttons which toggle it must be pressed an
+        // odd number of times in total if it should end up on.
+        let mut toggles = BitMatrix::new(self.indicator_lights.len(), self.buttons.len());
+        for (j, button) in self.buttons.iter().enumerate() {
+            for &light in button {
+                toggles.set(light, j, true);
+            }
+        }
+        let target: BitVector = self.indicator_lights.iter().copied().collect();
 
-        let target_state = self
-            .indicator_lights
-            .iter()
-            .enumerate()
-            .fold(0u64, |bits, (i, &on)| bits | ((on as u64) << i));
+        match toggles.min_weight_solution(&target) {
+            Ok(pressed) => Outcome::Solved(pressed.iter_ones().collect()),
+            Err(MinWeightError::TooDegenerate(_)) => Outcome::Undecided,
+            Err(MinWeightError::Inconsistent(certificate)) => {
+                let lights: Vec<usize> = certificate.iter_ones().collect();
+                Outcome::Infeasible(match lights[..] {
+                    [light] => format!("light {light} must be lit but no button toggles it"),
+                    _ => format!(
+                        "every button toggles an even number of lights {lights:?}, \
+                         but an odd number of them must be lit"
+                    ),
+                })
+            }
+        }
+    }
 
-        let button_masks: Vec<u64> = self
-            .buttons
-            .iter()
-            .filter_map(|button| {
-                let mask = button.iter().fold(0u64, |m, &idx| {
-                    debug_assert!(idx < num_lights);
-                    m | (1u64 << idx)
-                });
-                (mask != 0).then_some(mask)
+    /// Returns how many times to press each button to reach the joltage requirements in the fewest
+    /// presses overall.
+    fn shortest_press_plan_joltage(&self, solver: &dyn MinSumSolver) -> Outcome<Vec<i64>> {
+        // Each counter gives an equation over how many times each button which feeds it is pressed.
+        let counters: Vec<Vec<i64>> = (0..self.joltage_requirements.len())
+            .map(|i| {
+                self.buttons
+                    .iter()
+                    .map(|button| button.contains(&i) as i64)
+                    .collect()
             })
             .collect();
 
-        let num_states = 1usize << num_lights;
-        let mut min_presses = vec![u32::MAX; num_states];
-        let mut queue: VecDeque<u64> = VecDeque::from([0]);
-
-        min_presses[0] = 0;
-        while let Some(state) = queue.pop_front() {
-            let presses = min_presses[state as usize];
+        match solve_with_fallback(solver, &counters, &self.joltage_requirements) {
+            Solution::Optimal(presses) => Outcome::Solved(presses),
+            Solution::Undecided => Outcome::Undecided,
+            Solution::Infeasible(Infeasibility::Inconsistent(weights)) => {
+                let weighted: Vec<(usize, i64)> = (0..weights.len())
+                    .filter(|&i| weights[i] != 0)
+                    .map(|i| (i, weights[i]))
+                    .collect();
+                let total: i64 = weighted
+                    .iter()
+                    .map(|&(i, w)| w * self.joltage_requirements[i])
+                    .sum();
+                Outcome::Infeasible(match weighted[..] {
+                    [(counter, _)] => {
+                        format!("counter {counter} needs joltage but no button feeds it")
+                    }
+                    _ => format!(
+                        "weighting the counters as (counter, weight) {weighted:?} cancels out \
+                         every button, but leaves the requirements at {total}"
+                    ),
+                })
+            }
+            Solution::Infeasible(Infeasibility::NegativeTarget(counter)) => {
+                Outcome::Infeasible(format!("counter {counter} requires negative joltage"))
+            }
+            Solution::Infeasible(Infeasibility::NoNaturalSolution) => {
+                Outcome::Infeasible("no whole number of presses meets the requirements".to_string())
+            }
+        }
+    }
 
-            if state == target_state {
-                return presses as i64;
+    /// Re-simulates pressing each of `pressed` once, checking the indicators end up as required.
+    fn verify_lights(&self, pressed: &[usize]) -> bool {
+        let mut lights = vec![false; self.indicator_lights.len()];
+        for &button in pressed {
+            for &light in &self.buttons[button] {
+                lights[light] = !lights[light];
             }
+        }
+        lights == self.indicator_lights
+    }
 
-            for &button_mask in &button_masks {
-                let next_state = state ^ button_mask;
-                let idx = next_state as usize;
-                if min_presses[idx] == u32::MAX {
-                    min_presses[idx] = presses + 1;
-                    queue.push_back(next_state);
-                }
+    /// Re-simulates pressing every button `presses[i]` times, checking the counters end up as
+    /// required.
+    fn verify_joltage(&self, presses: &[i64]) -> bool {
+        let mut counters = vec![0; self.joltage_requirements.len()];
+        for (button, &count) in self.buttons.iter().zip(presses) {
+            for &counter in button {
+                counters[counter] += count;
             }
         }
+        presses.len() == self.buttons.len()
+            && presses.iter().all(|&count| count >= 0)
+            && counters == self.joltage_requirements
+    }
+}
 
-        unreachable!("Solving not possible");
+/// The press plans for a single machine.
+struct Plan {
+    lights: Outcome<Vec<usize>>,
+    joltage: Outcome<Vec<i64>>,
+}
+
+impl Plan {
+    fn for_machine(machine: &Machine, solver: &dyn MinSumSolver) -> Self {
+        let plan = Self {
+            lights: machine.shortest_press_plan_lights(),
+            joltage: machine.shortest_press_plan_joltage(solver),
+        };
+        debug_assert!(
+            plan.lights
+                .solved()
+                .is_none_or(|p| machine.verify_lights(p))
+        );
+        debug_assert!(
+            plan.joltage
+                .solved()
+                .is_none_or(|p| machine.verify_joltage(p))
+        );
+        plan
     }
 
-    fn shortest_press_count_joltage(&self) -> i64 {
-        // Solve this as a linear programming problem, using good_lp.
-        let mut vars = variables!();
-        let x: Vec<_> = (0..self.buttons.len())
-            .map(|_| vars.add(variable().min(0).integer()))
-            .collect();
+    #[inline(always)]
+    fn light_presses(&self) -> Option<i64> {
+        self.lights.solved().map(|pressed| pressed.len() as i64)
+    }
 
-        let mut problem = vars.minimise(x.iter().sum::<Expression>()).using(microlp);
+    #[inline(always)]
+    fn joltage_presses(&self) -> Option<i64> {
+        self.joltage.solved().map(|presses| presses.iter().sum())
+    }
+}
 
-        for (i, &target) in self.joltage_requirements.iter().enumerate() {
-            let expr: Expression = self
-                .buttons
-                .iter()
-                .enumerate()
-                .filter_map(|(j, button)| button.contains(&i).then_some(x[j]))
-                .sum();
-            problem = problem.with(constraint!(expr == target as f64));
+/// Describes one part of a plan, flagging it if it fails re-simulation.
+fn describe<T: Debug>(outcome: &Outcome<T>, presses: i64, verified: impl Fn(&T) -> bool) -> String {
+    match outcome {
+        Outcome::Solved(plan) => {
+            let status = if verified(plan) { "ok" } else { "FAILED" };
+            format!("{plan:?} = {presses} presses ({status})")
         }
+        _ => outcome.problem().unwrap(),
+    }
+}
 
-        let solution = problem.solve().unwrap();
-        x.iter().map(|&v| solution.value(v).round() as i64).sum()
+/// Prints each machine's plans to stderr.
+fn print_breakdown(machines: &[Machine], plans: &[Plan]) {
+    for (i, (machine, plan)) in machines.iter().zip(plans).enumerate() {
+        eprintln!(
+            "Machine {}: lights {}, joltage {}",
+            i + 1,
+            describe(&plan.lights, plan.light_presses().unwrap_or(0), |p| {
+                machine.verify_lights(p)
+            }),
+            describe(&plan.joltage, plan.joltage_presses().unwrap_or(0), |p| {
+                machine.verify_joltage(p)
+            }),
+        );
     }
 }
 
+/// Returns each part which can't be solved as its 1-based input line and the reason why.
+fn unsolvable_lines(plans: &[Plan]) -> Vec<(usize, String)> {
+    plans
+        .iter()
+        .enumerate()
+        .flat_map(|(i, plan)| {
+            let lights = plan.lights.problem().map(|p| format!("lights {p}"));
+            let joltage = plan.joltage.problem().map(|p| format!("joltage {p}"));
+            lights.into_iter().chain(joltage).map(move |p| (i + 1, p))
+        })
+        .collect()
+}
+
+/// Solves every machine, skipping (and reporting) any part which has no plan.
 #[inline(always)]
-fn day10(input: &str) -> (i64, i64) {
+fn day10(input: &str, solver: &dyn MinSumSolver) -> (i64, i64) {
     let machines: Vec<Machine> = input.lines().map(Machine::from_input_line).collect();
-
-    let part_one = machines
+    let plans: Vec<Plan> = machines
         .iter()
-        .map(Machine::shortest_press_count_lights)
-        .sum::<i64>();
+        .map(|machine| Plan::for_machine(machine, solver))
+        .collect();
 
-    let part_two = machines
-        .iter()
-        .map(Machine::shortest_press_count_joltage)
-        .sum::<i64>();
+    if is_verbose() {
+        print_breakdown(&machines, &plans);
+    }
+    for (line, problem) in unsolvable_lines(&plans) {
+        eprintln!("Line {line}: {problem}");
+    }
+
+    let part_one = plans.iter().filter_map(Plan::light_presses).sum::<i64>();
+    let part_two = plans.iter().filter_map(Plan::joltage_presses).sum::<i64>();
 
     (part_one, part_two)
 }
@@ -130,13 +258,32 @@ fn day10(input: &str) -> (i64, i64) {
 #[inline(always)]
 fn main() {
     let input = get_input_as_str();
-    let (p1, p2) = day10(input);
+
+    // Run every selected backend, timing and cross-checking them when there is more than one.
+    let solvers = selected_backends();
+    let answers: Vec<(i64, i64)> = solvers
+        .iter()
+        .map(|&solver| {
+            let start = Instant::now();
+            let answer = day10(input, solver);
+            if solvers.len() > 1 {
+                eprintln!("{}: {answer:?} in {:?}", solver.name(), start.elapsed());
+            }
+            answer
+        })
+        .collect();
+    if answers.iter().any(|answer| *answer != answers[0]) {
+        eprintln!("Solvers disagree: {answers:?}");
+    }
+
+    let (p1, p2) = answers[0];
     println!("{p1}\n{p2}");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::solver::{Enumeration, backends};
     use indoc::indoc;
 
     #[test]
@@ -175,7 +322,67 @@ mod tests {
     }
 
     #[test]
-    fn test_shortest_press_count_() {}
+    fn test_shortest_press_plans() {
+        let machine = Machine::from_input_line("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}");
+
+        let lights = machine.shortest_press_plan_lights();
+        assert_eq!(lights.solved().unwrap().len(), 2);
+        assert!(machine.verify_lights(lights.solved().unwrap()));
+        assert!(!machine.verify_lights(&[0]));
+
+        let joltage = machine.shortest_press_plan_joltage(&Enumeration);
+        assert_eq!(joltage.solved().unwrap().iter().sum::<i64>(), 10);
+        assert!(machine.verify_joltage(joltage.solved().unwrap()));
+        assert!(!machine.verify_joltage(&[3, 5, 4, 7, 0, 0]));
+    }
+
+    #[test]
+    fn test_unsolvable_lines() {
+        let machines = [
+            "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}",
+            "[#...] (1,2) (2,3) {0,3,4,1}",
+            "[.##.] (1,2) (2,3) {0,3,4,2}",
+        ];
+        let plans: Vec<Plan> = machines
+            .iter()
+            .map(|line| Plan::for_machine(&Machine::from_input_line(line), &Enumeration))
+            .collect();
+
+        assert_eq!(
+            unsolvable_lines(&plans),
+            [
+                (
+                    2,
+                    "lights infeasible: light 0 must be lit but no button toggles it".to_string()
+                ),
+                (
+                    3,
+                    "joltage infeasible: weighting the counters as (counter, weight) \
+                     [(1, 1), (2, -1), (3, 1)] cancels out every button, but leaves the \
+                     requirements at 1"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_too_many_buttons() {
+        // One light toggled by any of 22 buttons leaves too many combinations to try, which
+        // should be reported rather than abandon the run.
+        let line = format!("[#]{} {{22}}", " (0)".repeat(22));
+        let input = format!("{line}\n[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {{3,5,4,7}}\n");
+        let plans: Vec<Plan> = input
+            .lines()
+            .map(|line| Plan::for_machine(&Machine::from_input_line(line), &Enumeration))
+            .collect();
+        assert_eq!(
+            unsolvable_lines(&plans),
+            [(1, "lights undecided".to_string())]
+        );
+        assert_eq!(plans[0].joltage_presses(), Some(22));
+        assert_eq!(day10(&input, &Enumeration), (2, 32));
+    }
 
     #[test]
     fn test_day10() {
@@ -187,9 +394,11 @@ mod tests {
         const SAMPLE_PART1_ANSWER: i64 = 7;
         const SAMPLE_PART2_ANSWER: i64 = 33;
 
-        let (part1_answer, part2_answer) = day10(SAMPLE_INPUT);
-        assert_eq!(part1_answer, SAMPLE_PART1_ANSWER);
-        assert_eq!(part2_answer, SAMPLE_PART2_ANSWER);
+        for solver in backends() {
+            let (part1_answer, part2_answer) = day10(SAMPLE_INPUT, solver);
+            assert_eq!(part1_answer, SAMPLE_PART1_ANSWER);
+            assert_eq!(part2_answer, SAMPLE_PART2_ANSWER);
+        }
     }
 
     #[test]
@@ -201,6 +410,6 @@ mod tests {
             str::parse::<i64>(answers.0).unwrap(),
             str::parse::<i64>(answers.1).unwrap(),
         );
-        assert_eq!(day10(ACTUAL_INPUT), answers);
+        assert_eq!(day10(ACTUAL_INPUT, &Enumeration), answers);
     }
 }
@@ -1,85 +1,85 @@
+use aoc2025::dot::{Dot, dot_output_path};
 use aoc2025::get_input_as_str;
-use std::collections::HashMap;
+use aoc2025::graph::Graph;
 
-struct PathCounter<'a> {
-    devices: &'a HashMap<&'a str, Vec<&'a str>>,
-    target_cache: HashMap<&'a str, HashMap<&'a str, i64>>,
-}
-
-impl<'a> PathCounter<'a> {
-    fn new(devices: &'a HashMap<&'a str, Vec<&'a str>>) -> Self {
-        Self {
-            devices,
-            target_cache: HashMap::new(),
-        }
-    }
-
-    fn count(&mut self, from: &'a str, to: &'a str) -> i64 {
-        fn dfs<'a>(
-            devices: &'a HashMap<&'a str, Vec<&'a str>>,
-            node: &'a str,
-            target: &'a str,
-            memo: &mut HashMap<&'a str, i64>,
-        ) -> i64 {
-            if node == target {
-                return 1;
-            }
-
-            if let Some(&cached) = memo.get(node) {
-                return cached;
-            }
-
-            let total = devices
-                .get(node)
-                .map(|outputs| {
-                    outputs
-                        .iter()
-                        .map(|&next| dfs(devices, next, target, memo))
-                        .sum()
-                })
-                .unwrap_or(0);
-
-            memo.insert(node, total);
-            total
-        }
-
-        let memo = self.target_cache.entry(to).or_default();
-
-        dfs(self.devices, from, to, memo)
-    }
+/// Counts the paths between two named devices visiting every named waypoint, or zero if any of
+/// the devices are missing.
+#[inline(always)]
+fn count_paths(devices: &Graph, from: &str, to: &str, via: &[&str]) -> i64 {
+    let (Some(from), Some(to)) = (devices.node(from), devices.node(to)) else {
+        return 0;
+    };
+    let Some(via) = via
+        .iter()
+        .map(|&name| devices.node(name))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return 0;
+    };
+    let count = devices
+        .count_paths(from, to, &via)
+        .unwrap_or_else(|e| panic!("Can't count device paths: {e:?}"));
+    i64::try_from(count).unwrap()
 }
 
 #[inline(always)]
 fn day11(input: &str) -> (i64, i64) {
-    let devices: HashMap<&str, Vec<&str>> = input
-        .lines()
-        .map(|line| {
-            let (name, rest) = line.split_once(':').unwrap();
-            let outputs = rest.split_ascii_whitespace().collect();
-            (name, outputs)
-        })
-        .collect();
-
-    let mut counter = PathCounter::new(&devices);
+    let devices = Graph::from_adjacency_list(input);
 
     // Part 1: Simple single path.
-    let part_one = counter.count("you", "out");
+    let part_one = count_paths(&devices, "you", "out", &[]);
 
-    // Part 2: Trace via DAC then FFT, and FFT then DAC separately.
-    let svr_dac_fft_out =
-        counter.count("svr", "dac") * counter.count("dac", "fft") * counter.count("fft", "out");
-    let svr_fft_dac_out =
-        counter.count("svr", "fft") * counter.count("fft", "dac") * counter.count("dac", "out");
-    let part_two = svr_dac_fft_out + svr_fft_dac_out;
+    // Part 2: Paths which visit both the DAC and FFT, in either order.
+    let part_two = count_paths(&devices, "svr", "out", &["dac", "fft"]);
 
     (part_one, part_two)
 }
 
+/// Builds a DOT graph of the devices with the named devices highlighted, labelling each edge with
+/// the number of paths from "svr" (or "you") to "out" which use it.
+fn device_dot(devices: &Graph) -> Dot {
+    let mut dot = Dot::digraph(devices.nodes().map(|n| devices.name(n)));
+    for name in ["you", "svr", "dac", "fft", "out"] {
+        if let Some(node) = devices.node(name) {
+            dot.highlight(node);
+        }
+    }
+
+    let start = devices.node("svr").or(devices.node("you"));
+    let through = start.zip(devices.node("out")).map(|(start, out)| {
+        let to_edge = devices
+            .path_counts_from(start)
+            .unwrap_or_else(|e| panic!("Can't count device paths: {e:?}"));
+        let from_edge = devices.reverse().path_counts_from(out).unwrap();
+        (to_edge, from_edge)
+    });
+    for (from, to) in devices.edges() {
+        match &through {
+            Some((to_edge, from_edge)) => {
+                dot.labelled_edge(from, to, to_edge[from] * from_edge[to])
+            }
+            None => dot.edge(from, to),
+        };
+    }
+
+    let components = devices.weak_components();
+    if components.len() > 1 {
+        for component in components {
+            dot.cluster(component);
+        }
+    }
+    dot
+}
+
 #[inline(always)]
 fn main() {
     let input = get_input_as_str();
     let (p1, p2) = day11(input);
     println!("{p1}\n{p2}");
+
+    if dot_output_path().is_some() {
+        device_dot(&Graph::from_adjacency_list(input)).write_if_requested();
+    }
 }
 
 #[cfg(test)]
@@ -122,8 +122,18 @@ mod tests {
         let (part1_answer, _) = day11(SAMPLE_PART1_INPUT);
         assert_eq!(part1_answer, SAMPLE_PART1_ANSWER);
 
+        // A loop which no path from "you" can enter doesn't change the count.
+        let with_loop = format!("{SAMPLE_PART1_INPUT}zzz: yyy\nyyy: zzz out\n");
+        assert_eq!(day11(&with_loop).0, SAMPLE_PART1_ANSWER);
+
         let (_, part2_answer) = day11(SAMPLE_PART2_INPUT);
         assert_eq!(part2_answer, SAMPLE_PART2_ANSWER);
+
+        // Half of the eight server paths leave through each of its outputs.
+        let dot = device_dot(&Graph::from_adjacency_list(SAMPLE_PART2_INPUT)).to_string();
+        assert!(dot.contains("n0 [label=\"svr\", style=filled, fillcolor=gold];"));
+        assert!(dot.contains("n0 -> n1 [label=\"4\"];"));
+        assert!(dot.contains("n0 -> n2 [label=\"4\"];"));
     }
 
     #[test]
@@ -1,28 +1,53 @@
-use aoc2025::get_input_as_str;
-use atoi::atoi;
-use std::array;
+use aoc2025::budget::Budget;
+use aoc2025::polyomino::{
+    BUDGET_VAR, Disproof, Layout, Packing, Placement, Polyomino, pack_with_budget,
+};
+use aoc2025::{Grid, get_input_as_str, is_verbose, scan, sections};
+use std::io::{IsTerminal, stderr};
 
-const NUM_PRESENTS: usize = 6;
+/// A present's shape as drawn in the input, with its index and every way it can be placed.
+struct Shape {
+    index: usize,
+    grid: Grid,
+    orientations: Vec<Polyomino>,
+}
+
+impl Shape {
+    /// Parses a section of the form `N:` followed by rows of `#` and `.`.
+    #[inline(always)]
+    fn from_input(section: &str) -> Self {
+        let (header, rows) = section.split_once('\n').unwrap();
+        let (index,) = scan!(header.trim_end(), "{}:" => usize).unwrap();
+        let grid: Grid = rows.parse().unwrap();
+        let mut shape = Self {
+            index,
+            grid,
+            orientations: Vec::new(),
+        };
+        let cells = shape.cells().map(|(x, y)| (x as isize, y as isize));
+        shape.orientations = Polyomino::from_cells(cells).orientations();
+        shape
+    }
+
+    /// Returns the filled cells as drawn, in row-major order.
+    #[inline(always)]
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.grid.filter_iter(b'#').map(|p| p.index())
+    }
+}
 
 struct Region {
     width: usize,
     height: usize,
-    required_presents: [usize; NUM_PRESENTS],
+    /// How many of each present are required, by shape index.
+    required_presents: Vec<usize>,
 }
 
 impl Region {
     #[inline(always)]
     fn from_input(input: &str) -> Self {
-        let (dims, rest) = input.split_once(':').unwrap();
-        let (width_str, height_str) = dims.split_once('x').unwrap();
-
-        let width = atoi(width_str.as_bytes()).unwrap();
-        let height = atoi(height_str.as_bytes()).unwrap();
-
-        let mut required_presents = [0; NUM_PRESENTS];
-        for (i, s) in rest.split_ascii_whitespace().enumerate() {
-            required_presents[i] = atoi(s.as_bytes()).unwrap();
-        }
+        let (width, height, required_presents) =
+            scan!(input, "{}x{}: {}" => usize, usize, Vec<usize>).unwrap();
 
         Self {
             width,
@@ -31,49 +56,159 @@ impl Region {
         }
     }
 
+    /// Returns how many of each shape are required, in the order of `shapes`.
+    fn counts_for(&self, shapes: &[Shape]) -> Vec<usize> {
+        for (index, _) in self
+            .required_presents
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c > 0)
+        {
+            assert!(
+                shapes.iter().any(|s| s.index == index),
+                "Region requires present {index}, which has no shape"
+            );
+        }
+        shapes
+            .iter()
+            .map(|s| self.required_presents.get(s.index).copied().unwrap_or(0))
+            .collect()
+    }
+}
+
+/// Splits the input into the present shapes and the regions to fill.
+fn parse(input: &str) -> (Vec<Shape>, Vec<Region>) {
+    let mut shapes = Vec::new();
+    let mut regions = Vec::new();
+    for section in sections(input) {
+        let header = section.lines().next().unwrap_or("");
+        if header.contains('x') {
+            regions.extend(
+                section
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(Region::from_input),
+            );
+        } else {
+            shapes.push(Shape::from_input(section));
+        }
+    }
+    (shapes, regions)
+}
+
+/// Prints a packed region to stderr, coloured if stderr is a terminal, and writes it as a PPM
+/// image if one was requested.
+fn show_layout(i: usize, region: &Region, shapes: &[Shape], placements: &[Placement]) {
+    // Label placements by their shape's input index rather than its position in `shapes`.
+    let placements: Vec<Placement> = placements
+        .iter()
+        .map(|p| Placement {
+            shape: shapes[p.shape].index,
+            cells: p.cells.clone(),
+        })
+        .collect();
+    let layout = Layout::new(region.width, region.height, &placements);
+
+    if is_verbose() {
+        let grid = match stderr().is_terminal() {
+            true => layout.to_ansi(),
+            false => layout.to_string(),
+        };
+        eprintln!(
+            "Region {} ({}x{}):\n{grid}",
+            i + 1,
+            region.width,
+            region.height
+        );
+    }
+    layout.write_ppm_if_requested(&format!("day12-region{:04}", i + 1));
+}
+
+/// The search budget for each region unless [`BUDGET_VAR`] overrides it.
+const DEFAULT_BUDGET: Budget = Budget {
+    nodes: Some(1_000_000),
+    time: None,
+};
+
+/// How many regions ended up in each packing outcome.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+struct Summary {
+    fits: usize,
+    area: usize,
+    colouring: usize,
+    exhausted: usize,
+    undecided: usize,
+}
+
+impl Summary {
     #[inline(always)]
-    fn area(&self) -> usize {
-        self.width * self.height
+    fn record(&mut self, packing: &Packing) {
+        match packing {
+            Packing::Fits(_) => self.fits += 1,
+            Packing::DoesNotFit(Disproof::Area) => self.area += 1,
+            Packing::DoesNotFit(Disproof::Colouring) => self.colouring += 1,
+            Packing::DoesNotFit(Disproof::Exhausted) => self.exhausted += 1,
+            Packing::Undecided => self.undecided += 1,
+        }
+    }
+
+    /// Counts the regions which fit, trusting the area check for any left undecided.
+    #[inline(always)]
+    fn answer(&self) -> i64 {
+        (self.fits + self.undecided) as i64
     }
 }
 
+/// Packs every region within the budget, showing each one which fits.
+fn classify(input: &str, budget: Budget) -> Summary {
+    let (shapes, regions) = parse(input);
+    let orientations: Vec<_> = shapes.iter().map(|s| s.orientations.clone()).collect();
+
+    let mut summary = Summary::default();
+    for (i, region) in regions.iter().enumerate() {
+        let counts = region.counts_for(&shapes);
+        let packing = pack_with_budget(region.width, region.height, &orientations, &counts, budget);
+        if let Packing::Fits(placements) = &packing {
+            show_layout(i, region, &shapes, placements);
+        }
+        summary.record(&packing);
+    }
+    summary
+}
+
+/// Counts the regions which fit their presents. Regions the search can't settle within `budget`
+/// are counted as fitting because their area allows it, so a smaller budget can change the
+/// answer; a warning is printed whenever that happens.
 #[inline(always)]
-fn day12(input: &str) -> i64 {
-    let mut sections = input.split("\n\n");
-
-    // Count the number of blocks in each present.
-    let presents: [usize; NUM_PRESENTS] = array::from_fn(|_| {
-        sections
-            .next()
-            .unwrap()
-            .bytes()
-            .filter(|&b| b == b'#')
-            .count()
-    });
-
-    // Compare this to the optimal packing (which seems to be all you need to do).
-    sections
-        .next()
-        .unwrap_or("")
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(Region::from_input)
-        .filter(|region| {
-            region
-                .required_presents
-                .iter()
-                .zip(presents.iter())
-                .map(|(required, present)| required * present)
-                .sum::<usize>()
-                <= region.area()
-        })
-        .count() as i64
+fn day12(input: &str, budget: Budget) -> i64 {
+    let summary = classify(input, budget);
+    let Summary {
+        fits,
+        area,
+        colouring,
+        exhausted,
+        undecided,
+    } = summary;
+    if is_verbose() {
+        eprintln!(
+            "{fits} fit, {} don't ({area} by area, {colouring} by colouring, {exhausted} by \
+             search), {undecided} undecided",
+            area + colouring + exhausted
+        );
+    }
+    if undecided > 0 {
+        eprintln!(
+            "warning: {undecided} regions were undecided within the budget and are counted as \
+             fitting on area alone; raise {BUDGET_VAR} to settle them"
+        );
+    }
+    summary.answer()
 }
 
 #[inline(always)]
 fn main() {
     let input = get_input_as_str();
-    let p1 = day12(input);
+    let p1 = day12(input, Budget::from_env_or(BUDGET_VAR, DEFAULT_BUDGET));
     println!("{p1}");
 }
 
@@ -82,6 +217,34 @@ mod tests {
     use super::*;
     use indoc::indoc;
 
+    #[test]
+    fn test_parse() {
+        let input = indoc! {"
+            1:
+            ###
+            .#.
+
+            0:
+            ##
+
+            3x3: 2 1
+            5x5: 0 0 0 0
+        "};
+        let (shapes, regions) = parse(input);
+
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].index, 1);
+        assert_eq!((shapes[0].grid.width(), shapes[0].grid.height()), (3, 2));
+        assert!(shapes[0].cells().eq([(0, 0), (1, 0), (2, 0), (1, 1)]));
+        assert_eq!(shapes[0].orientations.len(), 4);
+        assert_eq!(shapes[1].orientations.len(), 2);
+
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].counts_for(&shapes), [1, 2]);
+        assert_eq!(regions[1].counts_for(&shapes), [0, 0]);
+        assert_eq!(day12(input, DEFAULT_BUDGET), 2);
+    }
+
     #[test]
     fn test_day12() {
         const SAMPLE_INPUT: &str = indoc! {"
@@ -120,11 +283,21 @@ mod tests {
             12x5: 1 0 1 0 3 2
         "};
 
-        // TODO: Work out why this is wrong when the real input is correct.
-        const SAMPLE_ANSWER: i64 = 2 + 1;
+        const SAMPLE_ANSWER: i64 = 2;
 
-        let part1_answer = day12(SAMPLE_INPUT);
+        let part1_answer = day12(SAMPLE_INPUT, DEFAULT_BUDGET);
         assert_eq!(part1_answer, SAMPLE_ANSWER);
+
+        let summary = classify(SAMPLE_INPUT, DEFAULT_BUDGET);
+        assert_eq!((summary.fits, summary.exhausted), (2, 1));
+
+        // Without any search no region fits in separate boxes, so all are left to the area check.
+        let budget = Budget {
+            nodes: Some(0),
+            time: None,
+        };
+        let summary = classify(SAMPLE_INPUT, budget);
+        assert_eq!((summary.fits, summary.undecided), (0, 3));
     }
 
     #[test]
@@ -132,6 +305,6 @@ mod tests {
         const ACTUAL_INPUT: &str = include_str!("../../data/inputs/day12.txt");
         const ACTUAL_ANSWERS: &str = include_str!("../../data/answers/day12.txt");
         let answer = str::parse::<i64>(ACTUAL_ANSWERS).unwrap();
-        assert_eq!(day12(ACTUAL_INPUT), answer);
+        assert_eq!(day12(ACTUAL_INPUT, DEFAULT_BUDGET), answer);
     }
 }
@@ -0,0 +1,154 @@
+use std::env;
+use std::time::{Duration, Instant};
+
+/// Limits on how much a search may do. Unlimited by default.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Budget {
+    /// The most nodes to visit, where each search decides what a node is.
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl Budget {
+    /// Reads the budget from the environment variable `var` as a node count such as `1000000` or
+    /// a time such as `250ms` or `2s`, falling back to `default` if it is unset. Panics if it
+    /// can't be parsed.
+    pub fn from_env_or(var: &str, default: Self) -> Self {
+        let Ok(value) = env::var(var) else {
+            return default;
+        };
+        let time = |n: Option<&str>, unit: f64| {
+            let n: f64 = n?.parse().ok()?;
+            Some(Self {
+                nodes: None,
+                time: Some(Duration::from_secs_f64(n * unit)),
+            })
+        };
+        let budget = match value.strip_suffix("ms") {
+            Some(ms) => time(Some(ms), 1e-3),
+            None => time(value.strip_suffix('s'), 1.0).or_else(|| {
+                value.parse().ok().map(|nodes| Self {
+                    nodes: Some(nodes),
+                    time: None,
+                })
+            }),
+        };
+        budget.unwrap_or_else(|| panic!("Invalid {var} {value:?}, expected nodes, ms or s"))
+    }
+}
+
+/// Why a search gave up before exhausting the problem.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Stopped {
+    /// The node or time budget ran out.
+    Budget,
+    /// The cancellation hook asked to stop.
+    Cancelled,
+}
+
+/// How many nodes are visited between checks of the clock and the cancellation hook.
+pub const POLL_INTERVAL: u64 = 256;
+
+/// Tracks a search against its [`Budget`] and an optional cancellation hook.
+pub struct Meter<'a> {
+    nodes: u64,
+    node_limit: u64,
+    deadline: Option<Instant>,
+    cancel: Option<Box<dyn FnMut() -> bool + 'a>>,
+    stopped: Option<Stopped>,
+}
+
+impl<'a> Meter<'a> {
+    /// Starts metering, with the time budget running from now.
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            nodes: 0,
+            node_limit: budget.nodes.unwrap_or(u64::MAX),
+            deadline: budget.time.map(|time| Instant::now() + time),
+            cancel: None,
+            stopped: None,
+        }
+    }
+
+    /// Stops the search once `cancel` returns true. It is polled every [`POLL_INTERVAL`] nodes.
+    #[inline(always)]
+    pub fn with_cancel(mut self, cancel: impl FnMut() -> bool + 'a) -> Self {
+        self.cancel = Some(Box::new(cancel));
+        self
+    }
+
+    /// Returns how many nodes have been visited.
+    #[inline(always)]
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    #[inline(always)]
+    pub fn stopped(&self) -> Option<Stopped> {
+        self.stopped
+    }
+
+    /// Counts a node, or fails without counting it once the search has to stop.
+    #[inline(always)]
+    pub fn tick(&mut self) -> Result<(), Stopped> {
+        if self.stopped.is_none() {
+            self.stopped = self.check();
+        }
+        match self.stopped {
+            Some(stopped) => Err(stopped),
+            None => {
+                self.nodes += 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn check(&mut self) -> Option<Stopped> {
+        if self.nodes >= self.node_limit {
+            return Some(Stopped::Budget);
+        }
+        if !self.nodes.is_multiple_of(POLL_INTERVAL) {
+            return None;
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Some(Stopped::Budget);
+        }
+        self.cancel
+            .as_mut()
+            .is_some_and(|cancel| cancel())
+            .then_some(Stopped::Cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter() {
+        let mut meter = Meter::new(Budget {
+            nodes: Some(3),
+            time: None,
+        });
+        assert_eq!((0..5).filter(|_| meter.tick().is_ok()).count(), 3);
+        assert_eq!(meter.stopped(), Some(Stopped::Budget));
+
+        let mut meter = Meter::new(Budget {
+            nodes: None,
+            time: Some(Duration::ZERO),
+        });
+        assert_eq!(meter.tick(), Err(Stopped::Budget));
+
+        let mut polls = 0;
+        let mut meter = Meter::new(Budget::default()).with_cancel(|| {
+            polls += 1;
+            polls > 2
+        });
+        while meter.tick().is_ok() {}
+        assert_eq!(meter.nodes(), 2 * POLL_INTERVAL);
+        assert_eq!(meter.stopped(), Some(Stopped::Cancelled));
+    }
+}
@@ -0,0 +1,142 @@
+/// Maps a sparse set of coordinates onto dense indices. Consecutive coordinates `c[i]..c[i + 1]`
+/// also form half-open blocks, so any value between the first and last coordinate belongs to
+/// exactly one block.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct CompressedAxis {
+    coords: Vec<i64>,
+}
+
+impl FromIterator<i64> for CompressedAxis {
+    fn from_iter<I: IntoIterator<Item = i64>>(iter: I) -> Self {
+        let mut coords: Vec<i64> = iter.into_iter().collect();
+        coords.sort_unstable();
+        coords.dedup();
+        Self { coords }
+    }
+}
+
+impl CompressedAxis {
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.coords.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
+    }
+
+    /// Returns the number of half-open blocks between consecutive coordinates.
+    #[inline(always)]
+    pub fn num_blocks(&self) -> usize {
+        self.coords.len().saturating_sub(1)
+    }
+
+    #[inline(always)]
+    pub fn coords(&self) -> &[i64] {
+        &self.coords
+    }
+
+    #[inline(always)]
+    pub fn coord(&self, index: usize) -> i64 {
+        self.coords[index]
+    }
+
+    /// Returns the dense index of a coordinate which was part of the input set.
+    #[inline(always)]
+    pub fn index_of(&self, x: i64) -> Option<usize> {
+        self.coords.binary_search(&x).ok()
+    }
+
+    /// Returns the block `c[i]..c[i + 1]` containing `x`, if any.
+    #[inline(always)]
+    pub fn block_of(&self, x: i64) -> Option<usize> {
+        let i = self.coords.partition_point(|&c| c <= x);
+        (i > 0 && i < self.coords.len()).then(|| i - 1)
+    }
+
+    /// Returns the number of integers within a block.
+    #[inline(always)]
+    pub fn block_len(&self, block: usize) -> i64 {
+        self.coords[block + 1] - self.coords[block]
+    }
+}
+
+/// A 2D prefix sum table answering inclusive rectangle sums in O(1).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SummedAreaTable {
+    width: usize,
+    height: usize,
+    sums: Vec<i64>,
+}
+
+impl SummedAreaTable {
+    /// Builds the table from a `width` by `height` grid of values given by `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> i64) -> Self {
+        let stride = width + 1;
+        let mut sums = vec![0; stride * (height + 1)];
+        for y in 0..height {
+            let mut row = 0;
+            for x in 0..width {
+                row += f(x, y);
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row;
+            }
+        }
+        Self {
+            width,
+            height,
+            sums,
+        }
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the sum of all values with `x0 <= x <= x1` and `y0 <= y <= y1`.
+    #[inline(always)]
+    pub fn sum(&self, x0: usize, y0: usize, x1: usize, y1: usize) -> i64 {
+        debug_assert!(x0 <= x1 && x1 < self.width && y0 <= y1 && y1 < self.height);
+        let stride = self.width + 1;
+        self.sums[(y1 + 1) * stride + x1 + 1]
+            - self.sums[y0 * stride + x1 + 1]
+            - self.sums[(y1 + 1) * stride + x0]
+            + self.sums[y0 * stride + x0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_axis() {
+        let axis: CompressedAxis = [100, -5, 7, 100, 8].into_iter().collect();
+        assert_eq!(axis.coords(), &[-5, 7, 8, 100]);
+        assert_eq!(axis.index_of(8), Some(2));
+        assert_eq!(axis.index_of(9), None);
+        assert_eq!(axis.block_of(-5), Some(0));
+        assert_eq!(axis.block_of(7), Some(1));
+        assert_eq!(axis.block_of(99), Some(2));
+        assert_eq!(axis.block_of(100), None);
+        assert_eq!(axis.block_of(-6), None);
+        assert_eq!(axis.block_len(2), 92);
+    }
+
+    #[test]
+    fn test_summed_area_table() {
+        let table = SummedAreaTable::from_fn(4, 3, |x, y| (x * 10 + y) as i64);
+        assert_eq!(
+            table.sum(0, 0, 3, 2),
+            (0..4).map(|x| 30 * x + 3).sum::<i64>()
+        );
+        assert_eq!(table.sum(1, 1, 2, 2), 11 + 12 + 21 + 22);
+        assert_eq!(table.sum(3, 0, 3, 0), 30);
+    }
+}
@@ -0,0 +1,160 @@
+use std::ffi::OsString;
+use std::fmt::{self, Display, Formatter, Write};
+use std::{env, fs};
+
+/// Environment variable naming the file a day should write its DOT graph to.
+pub const DOT_OUTPUT_VAR: &str = "AOC_DOT";
+
+/// Returns the requested DOT output path, if any.
+#[inline(always)]
+pub fn dot_output_path() -> Option<OsString> {
+    env::var_os(DOT_OUTPUT_VAR)
+}
+
+/// A Graphviz graph description, rendered by its [`Display`] implementation.
+#[derive(Debug, Clone, Default)]
+pub struct Dot {
+    directed: bool,
+    labels: Vec<String>,
+    highlighted: Vec<bool>,
+    clusters: Vec<Vec<usize>>,
+    edges: Vec<(usize, usize, Option<String>)>,
+}
+
+impl Dot {
+    /// Creates a directed graph with one node per label.
+    pub fn digraph(labels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+        Self {
+            directed: true,
+            highlighted: vec![false; labels.len()],
+            labels,
+            ..Self::default()
+        }
+    }
+
+    /// Creates an undirected graph with one node per label.
+    pub fn graph(labels: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            directed: false,
+            ..Self::digraph(labels)
+        }
+    }
+
+    #[inline(always)]
+    pub fn highlight(&mut self, node: usize) -> &mut Self {
+        self.highlighted[node] = true;
+        self
+    }
+
+    #[inline(always)]
+    pub fn edge(&mut self, from: usize, to: usize) -> &mut Self {
+        self.edges.push((from, to, None));
+        self
+    }
+
+    #[inline(always)]
+    pub fn labelled_edge(&mut self, from: usize, to: usize, label: impl Display) -> &mut Self {
+        self.edges.push((from, to, Some(label.to_string())));
+        self
+    }
+
+    /// Groups each set of nodes into its own boxed subgraph. Nodes may belong to one cluster only.
+    #[inline(always)]
+    pub fn cluster(&mut self, nodes: impl IntoIterator<Item = usize>) -> &mut Self {
+        self.clusters.push(nodes.into_iter().collect());
+        self
+    }
+
+    /// Writes the graph to the file named by [`DOT_OUTPUT_VAR`], if it is set.
+    pub fn write_if_requested(&self) {
+        if let Some(path) = dot_output_path() {
+            fs::write(path, self.to_string()).expect("Could not write DOT output");
+        }
+    }
+
+    fn write_node(&self, f: &mut Formatter<'_>, indent: &str, node: usize) -> fmt::Result {
+        write!(f, "{indent}n{node} [label=")?;
+        write_quoted(f, &self.labels[node])?;
+        if self.highlighted[node] {
+            f.write_str(", style=filled, fillcolor=gold")?;
+        }
+        f.write_str("];\n")
+    }
+}
+
+/// Writes a string as a DOT quoted identifier.
+fn write_quoted(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_char('"')
+}
+
+impl Display for Dot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (keyword, arrow) = match self.directed {
+            true => ("digraph", "->"),
+            false => ("graph", "--"),
+        };
+        writeln!(f, "{keyword} {{")?;
+
+        let mut clustered = vec![false; self.labels.len()];
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            writeln!(f, "  subgraph cluster_{i} {{")?;
+            for &node in cluster {
+                clustered[node] = true;
+                self.write_node(f, "    ", node)?;
+            }
+            writeln!(f, "  }}")?;
+        }
+        for node in (0..self.labels.len()).filter(|&n| !clustered[n]) {
+            self.write_node(f, "  ", node)?;
+        }
+
+        for (from, to, label) in &self.edges {
+            write!(f, "  n{from} {arrow} n{to}")?;
+            if let Some(label) = label {
+                f.write_str(" [label=")?;
+                write_quoted(f, label)?;
+                f.write_char(']')?;
+            }
+            f.write_str(";\n")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_dot() {
+        let mut dot = Dot::graph(["a", "b \"quoted\"", "c"]);
+        dot.highlight(2)
+            .cluster([0, 1])
+            .edge(0, 1)
+            .labelled_edge(1, 2, 4.5);
+        assert_eq!(
+            dot.to_string(),
+            indoc! {r#"
+                graph {
+                  subgraph cluster_0 {
+                    n0 [label="a"];
+                    n1 [label="b \"quoted\""];
+                  }
+                  n2 [label="c", style=filled, fillcolor=gold];
+                  n0 -- n1;
+                  n1 -- n2 [label="4.5"];
+                }
+            "#}
+        );
+    }
+}
@@ -0,0 +1,131 @@
+/// Disjoint-set forest with path compression and union by size.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    /// Creates `n` singleton sets.
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Returns the representative of the set containing `x`.
+    #[inline(always)]
+    pub fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            // Path halving: point every other node at its grandparent.
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merges the sets containing `a` and `b`, returning false if they were already merged.
+    #[inline(always)]
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    #[inline(always)]
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the number of disjoint sets.
+    #[inline(always)]
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the size of the set containing `x`.
+    #[inline(always)]
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Returns the sizes of every set, in no particular order.
+    #[inline(always)]
+    pub fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|root| self.size[root])
+    }
+
+    /// Returns the sizes of the `k` largest sets, largest first.
+    pub fn top_k_sizes(&self, k: usize) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.sizes().collect();
+        if k < sizes.len() {
+            sizes.select_nth_unstable_by(k, |a, b| b.cmp(a));
+            sizes.truncate(k);
+        }
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// Returns the members of every set, each in ascending order, ordered by their smallest member.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut slot = vec![usize::MAX; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if slot[root] == usize::MAX {
+                slot[root] = components.len();
+                components.push(Vec::with_capacity(self.size[root]));
+            }
+            components[slot[root]].push(x);
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut dsu = DisjointSet::new(8);
+        assert!(dsu.union(0, 3));
+        assert!(dsu.union(3, 5));
+        assert!(dsu.union(6, 7));
+        assert!(!dsu.union(5, 0));
+
+        assert_eq!(dsu.component_count(), 5);
+        assert_eq!(dsu.size_of(5), 3);
+        assert!(dsu.same(0, 5) && !dsu.same(0, 6));
+        assert_eq!(dsu.top_k_sizes(2), [3, 2]);
+        assert_eq!(dsu.top_k_sizes(10), [3, 2, 1, 1, 1]);
+        assert_eq!(
+            dsu.components(),
+            [vec![0, 3, 5], vec![1], vec![2], vec![4], vec![6, 7]]
+        );
+    }
+}
@@ -0,0 +1,513 @@
+use crate::budget::{Budget, Meter, Stopped};
+
+/// An exact cover problem: choose a set of rows which covers every primary column exactly once,
+/// or as many times as its multiplicity, and every secondary column at most once.
+#[derive(Debug, Clone, Default)]
+pub struct ExactCover {
+    primary: usize,
+    secondary: usize,
+    multiplicities: Vec<usize>,
+    rows: Vec<Vec<usize>>,
+}
+
+impl ExactCover {
+    /// Creates a problem with no rows. Columns `0..primary` are primary and the following
+    /// `secondary` columns are secondary.
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        Self {
+            primary,
+            secondary,
+            multiplicities: vec![1; primary],
+            rows: Vec::new(),
+        }
+    }
+
+    /// Requires a primary column to be covered by exactly `count` rows. Solutions are still sets
+    /// of rows, so the same rows chosen in another order are only found once.
+    pub fn set_multiplicity(&mut self, column: usize, count: usize) {
+        assert!(
+            column < self.primary,
+            "Only primary columns have a multiplicity"
+        );
+        assert!(count > 0, "Multiplicities must be positive");
+        self.multiplicities[column] = count;
+    }
+
+    #[inline(always)]
+    pub fn columns(&self) -> usize {
+        self.primary + self.secondary
+    }
+
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Adds a row covering the given columns, returning its index.
+    pub fn add_row(&mut self, columns: impl IntoIterator<Item = usize>) -> usize {
+        let mut row: Vec<usize> = columns.into_iter().collect();
+        assert!(
+            row.iter().all(|&c| c < self.columns()),
+            "Row refers to a column which does not exist"
+        );
+        row.sort_unstable();
+        assert!(
+            row.windows(2).all(|w| w[0] != w[1]),
+            "Row covers a column twice"
+        );
+        self.rows.push(row);
+        self.rows.len() - 1
+    }
+
+    /// Starts a search over every solution, without limits until they are set on it.
+    #[inline(always)]
+    pub fn search(&self) -> Search<'_> {
+        Search::new(self)
+    }
+
+    /// Returns the rows of the first solution found, if there is one.
+    #[inline(always)]
+    pub fn first_solution(&self) -> Option<Vec<usize>> {
+        self.search().next()
+    }
+
+    #[inline(always)]
+    pub fn count_solutions(&self) -> u64 {
+        self.search().count_solutions().unwrap()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum State {
+    Fresh,
+    Running,
+    Done,
+}
+
+/// One level of the search: the column it branches on, the row it has taken there and how many
+/// earlier rows of the column it has ruled out.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    column: usize,
+    taken: Option<usize>,
+    excluded: usize,
+}
+
+/// Knuth's Algorithm X over dancing links, run with an explicit stack so that it can pause at
+/// each solution. Iterating yields the rows of each solution in ascending order and ends early if
+/// the search is stopped, which [`Search::stopped`] then reports.
+///
+/// Each level branches on the first row a column's solutions take from it, ruling that row out
+/// for the levels after, so a column which needs several rows sees each set of them once.
+pub struct Search<'a> {
+    // Node 0 is the root, nodes 1..=columns are the column headers and the rest are row entries.
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+    /// How many more rows each column needs, covering it once none are.
+    need: Vec<usize>,
+    frames: Vec<Frame>,
+    /// The entry node of each row ruled out by a frame, restored when the frame is done.
+    excluded: Vec<usize>,
+    state: State,
+    /// Counts each taken row as a node.
+    meter: Meter<'a>,
+}
+
+impl<'a> Search<'a> {
+    fn new(problem: &ExactCover) -> Self {
+        let columns = problem.columns();
+        let entries: usize = problem.rows.iter().map(Vec::len).sum();
+        let nodes = 1 + columns + entries;
+
+        let mut search = Self {
+            left: Vec::with_capacity(nodes),
+            right: Vec::with_capacity(nodes),
+            up: Vec::with_capacity(nodes),
+            down: Vec::with_capacity(nodes),
+            column: Vec::with_capacity(nodes),
+            row: Vec::with_capacity(nodes),
+            size: vec![0; columns + 1],
+            need: std::iter::once(0)
+                .chain(problem.multiplicities.iter().copied())
+                .chain(std::iter::repeat_n(1, problem.secondary))
+                .collect(),
+            frames: Vec::new(),
+            excluded: Vec::new(),
+            state: State::Fresh,
+            meter: Meter::new(Budget::default()),
+        };
+
+        // Primary headers form a ring with the root, secondary headers are each a ring of one so
+        // they are never chosen but are still covered by the rows which use them.
+        for node in 0..=columns {
+            let in_ring = node <= problem.primary;
+            let (l, r) = match in_ring {
+                true => ((node + problem.primary) % (problem.primary + 1), 0),
+                false => (node, node),
+            };
+            search.left.push(l);
+            search.right.push(r);
+            search.up.push(node);
+            search.down.push(node);
+            search.column.push(node);
+            search.row.push(usize::MAX);
+        }
+        for node in 0..problem.primary {
+            search.right[node] = node + 1;
+        }
+
+        for (r, columns) in problem.rows.iter().enumerate() {
+            let first = search.left.len();
+            for (i, &c) in columns.iter().enumerate() {
+                let node = first + i;
+                let header = c + 1;
+                search.left.push(if i == 0 {
+                    first + columns.len() - 1
+                } else {
+                    node - 1
+                });
+                search.right.push(if i + 1 == columns.len() {
+                    first
+                } else {
+                    node + 1
+                });
+                search.up.push(search.up[header]);
+                search.down.push(header);
+                search.column.push(header);
+                search.row.push(r);
+                let last = search.up[header];
+                search.down[last] = node;
+                search.up[header] = node;
+                search.size[header] += 1;
+            }
+        }
+        search
+    }
+
+    /// Stops the search once `budget` is spent, counting each row taken as a node. The time
+    /// budget runs from this call.
+    #[inline(always)]
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.meter = Meter::new(budget);
+        self
+    }
+
+    /// Stops the search once `cancel` returns true, as [`Meter::with_cancel`].
+    #[inline(always)]
+    pub fn with_cancel(mut self, cancel: impl FnMut() -> bool + 'a) -> Self {
+        self.meter = self.meter.with_cancel(cancel);
+        self
+    }
+
+    /// Returns how many rows have been taken so far.
+    #[inline(always)]
+    pub fn nodes(&self) -> u64 {
+        self.meter.nodes()
+    }
+
+    #[inline(always)]
+    pub fn stopped(&self) -> Option<Stopped> {
+        self.meter.stopped()
+    }
+
+    /// Returns the rows of the next solution, or `None` once there are no more.
+    pub fn next_solution(&mut self) -> Result<Option<Vec<usize>>, Stopped> {
+        Ok(self.advance()?.then(|| self.solution()))
+    }
+
+    /// Counts the remaining solutions.
+    pub fn count_solutions(&mut self) -> Result<u64, Stopped> {
+        let mut count = 0;
+        while self.advance()? {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn solution(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self
+            .frames
+            .iter()
+            .filter_map(|frame| frame.taken)
+            .map(|node| self.row[node])
+            .collect();
+        rows.sort_unstable();
+        rows
+    }
+
+    /// Runs until the taken rows form the next solution, returning false once there are none.
+    fn advance(&mut self) -> Result<bool, Stopped> {
+        if let Some(stopped) = self.meter.stopped() {
+            return Err(stopped);
+        }
+        let mut descend = match self.state {
+            State::Fresh => true,
+            State::Running => false,
+            State::Done => return Ok(false),
+        };
+        self.state = State::Running;
+
+        loop {
+            if descend {
+                if self.right[0] == 0 {
+                    return Ok(true);
+                }
+                self.frames.push(Frame {
+                    column: self.choose_column(),
+                    taken: None,
+                    excluded: 0,
+                });
+            }
+
+            // Move the innermost level on to its next row, or backtrack out of it if there are
+            // too few left.
+            let Some(&frame) = self.frames.last() else {
+                self.state = State::Done;
+                return Ok(false);
+            };
+            let mut frame = frame;
+            if let Some(node) = frame.taken.take() {
+                self.untake(node);
+                self.excluded.push(node);
+                frame.excluded += 1;
+            }
+            let c = frame.column;
+            if self.size[c] < self.need[c] {
+                for _ in 0..frame.excluded {
+                    let node = self.excluded.pop().unwrap();
+                    self.unhide(node);
+                }
+                self.frames.pop();
+                descend = false;
+                continue;
+            }
+
+            self.meter.tick()?;
+            let node = self.down[c];
+            self.take(node);
+            frame.taken = Some(node);
+            *self.frames.last_mut().unwrap() = frame;
+            descend = true;
+        }
+    }
+
+    /// Picks the primary column with the fewest rows to spare over those it still needs.
+    #[inline(always)]
+    fn choose_column(&self) -> usize {
+        let branches = |c: usize| (self.size[c] + 1).saturating_sub(self.need[c]);
+        let mut best = self.right[0];
+        let mut c = self.right[best];
+        while c != 0 && branches(best) > 0 {
+            if branches(c) < branches(best) {
+                best = c;
+            }
+            c = self.right[c];
+        }
+        best
+    }
+
+    /// Adds a row to the solution, covering each of its columns which needs no more rows.
+    fn take(&mut self, node: usize) {
+        self.hide(node);
+        let mut j = node;
+        loop {
+            let c = self.column[j];
+            self.need[c] -= 1;
+            if self.need[c] == 0 {
+                self.cover(c);
+            }
+            j = self.right[j];
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    /// Removes a row from the solution, leaving it hidden.
+    fn untake(&mut self, node: usize) {
+        let mut j = self.left[node];
+        loop {
+            let c = self.column[j];
+            if self.need[c] == 0 {
+                self.uncover(c);
+            }
+            self.need[c] += 1;
+            if j == node {
+                break;
+            }
+            j = self.left[j];
+        }
+    }
+
+    /// Unlinks every entry of a row from its column.
+    fn hide(&mut self, node: usize) {
+        let mut j = node;
+        loop {
+            let (u, d) = (self.up[j], self.down[j]);
+            self.down[u] = d;
+            self.up[d] = u;
+            self.size[self.column[j]] -= 1;
+            j = self.right[j];
+            if j == node {
+                break;
+            }
+        }
+    }
+
+    fn unhide(&mut self, node: usize) {
+        let mut j = self.left[node];
+        loop {
+            self.size[self.column[j]] += 1;
+            let (u, d) = (self.up[j], self.down[j]);
+            self.down[u] = j;
+            self.up[d] = j;
+            if j == node {
+                break;
+            }
+            j = self.left[j];
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = r;
+        self.left[r] = l;
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                let (u, d) = (self.up[j], self.down[j]);
+                self.down[u] = j;
+                self.up[d] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (l, r) = (self.left[c], self.right[c]);
+        self.right[l] = c;
+        self.left[r] = c;
+    }
+}
+
+impl Iterator for Search<'_> {
+    type Item = Vec<usize>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_solution().ok().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::POLL_INTERVAL;
+
+    /// Places `n` queens with one primary column per rank and file, and the diagonals secondary.
+    fn queens(n: usize) -> ExactCover {
+        let diagonals = 2 * n - 1;
+        let mut problem = ExactCover::new(2 * n, 2 * diagonals);
+        for rank in 0..n {
+            for file in 0..n {
+                let diagonal = 2 * n + rank + file;
+                let anti_diagonal = 2 * n + diagonals + rank + n - 1 - file;
+                problem.add_row([rank, n + file, diagonal, anti_diagonal]);
+            }
+        }
+        problem
+    }
+
+    #[test]
+    fn test_exact_cover() {
+        // Knuth's example from the Dancing Links paper, with columns A to G.
+        let mut problem = ExactCover::new(7, 0);
+        for row in [
+            &[2, 4, 5][..],
+            &[0, 3, 6],
+            &[1, 2, 5],
+            &[0, 3],
+            &[1, 6],
+            &[3, 4, 6],
+        ] {
+            problem.add_row(row.iter().copied());
+        }
+        assert_eq!(problem.first_solution(), Some(vec![0, 3, 4]));
+        assert_eq!(problem.count_solutions(), 1);
+
+        assert_eq!(ExactCover::new(0, 0).count_solutions(), 1);
+        assert_eq!(ExactCover::new(1, 0).first_solution(), None);
+    }
+
+    #[test]
+    fn test_secondary_columns() {
+        assert_eq!(queens(4).search().collect::<Vec<_>>().len(), 2);
+        assert_eq!(queens(6).count_solutions(), 4);
+        assert_eq!(queens(8).count_solutions(), 92);
+    }
+
+    #[test]
+    fn test_multiplicities() {
+        // Two dominoes tile a 2x2 square either side by side or stacked, whichever is first.
+        let mut problem = ExactCover::new(5, 0);
+        problem.set_multiplicity(4, 2);
+        for cells in [[0, 1], [2, 3], [0, 2], [1, 3]] {
+            problem.add_row(cells.into_iter().chain([4]));
+        }
+        assert_eq!(problem.count_solutions(), 2);
+        assert_eq!(problem.search().collect::<Vec<_>>(), [[0, 1], [2, 3]]);
+
+        problem.set_multiplicity(4, 3);
+        assert_eq!(problem.count_solutions(), 0);
+
+        // Three of four interchangeable rows, with a fourth column taking the remaining one.
+        let mut problem = ExactCover::new(2, 0);
+        problem.set_multiplicity(0, 3);
+        for _ in 0..4 {
+            problem.add_row([0]);
+        }
+        problem.add_row([1]);
+        assert_eq!(problem.count_solutions(), 4);
+    }
+
+    #[test]
+    fn test_limits() {
+        let problem = queens(8);
+        let mut search = problem.search().with_budget(Budget {
+            nodes: Some(100),
+            time: None,
+        });
+        assert_eq!(search.count_solutions(), Err(Stopped::Budget));
+        assert_eq!(search.nodes(), 100);
+        assert_eq!(search.next(), None);
+        assert_eq!(search.stopped(), Some(Stopped::Budget));
+
+        let mut polls = 0;
+        let mut search = problem.search().with_cancel(|| {
+            polls += 1;
+            polls > 2
+        });
+        assert_eq!(search.count_solutions(), Err(Stopped::Cancelled));
+        assert_eq!(search.nodes(), 2 * POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::parse::{ParseInt, parse_line_ints};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// Integer types usable as geometry coordinates.
+pub trait Coord:
+    ParseInt
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + AddAssign
+    + SubAssign
+    + Sum
+    + std::fmt::Debug
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Returns `|a - b|` without underflowing unsigned types.
+    #[inline(always)]
+    fn abs_diff(a: Self, b: Self) -> Self {
+        if a > b { a - b } else { b - a }
+    }
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {$(
+        impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        }
+    )*};
+}
+
+impl_coord!(i16, i32, i64, isize, u16, u32, u64, usize);
+
+#[derive(Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
+pub struct Point2D<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Point2D<T> {
+    #[inline(always)]
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Parses a point of the form "x,y".
+    #[inline(always)]
+    pub fn from_csv_str(s: &str) -> Self {
+        let [x, y] = parse_line_ints(s.as_bytes());
+        Self { x, y }
+    }
+
+    #[inline(always)]
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        T::abs_diff(self.x, other.x) + T::abs_diff(self.y, other.y)
+    }
+
+    #[inline(always)]
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        T::abs_diff(self.x, other.x).max(T::abs_diff(self.y, other.y))
+    }
+
+    #[inline(always)]
+    pub fn squared_distance(&self, other: &Self) -> T {
+        let dx = T::abs_diff(self.x, other.x);
+        let dy = T::abs_diff(self.y, other.y);
+        dx * dx + dy * dy
+    }
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Ord, PartialOrd)]
+pub struct Point3D<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point3D<T> {
+    #[inline(always)]
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Parses a point of the form "x,y,z".
+    #[inline(always)]
+    pub fn from_csv_str(s: &str) -> Self {
+        let [x, y, z] = parse_line_ints(s.as_bytes());
+        Self { x, y, z }
+    }
+
+    #[inline(always)]
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        T::abs_diff(self.x, other.x) + T::abs_diff(self.y, other.y) + T::abs_diff(self.z, other.z)
+    }
+
+    #[inline(always)]
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        T::abs_diff(self.x, other.x)
+            .max(T::abs_diff(self.y, other.y))
+            .max(T::abs_diff(self.z, other.z))
+    }
+
+    #[inline(always)]
+    pub fn squared_distance(&self, other: &Self) -> T {
+        let dx = T::abs_diff(self.x, other.x);
+        let dy = T::abs_diff(self.y, other.y);
+        let dz = T::abs_diff(self.z, other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Returns the minimum and maximum corners of the box containing all points.
+    pub fn bounding_box<'a, I>(points: I) -> Option<(Self, Self)>
+    where
+        I: IntoIterator<Item = &'a Self>,
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Self::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                Self::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            )
+        }))
+    }
+}
+
+macro_rules! impl_point_ops {
+    ($point:ident { $($field:ident),* }) => {
+        impl<T: Coord> Add for $point<T> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                Self { $($field: self.$field + rhs.$field),* }
+            }
+        }
+
+        impl<T: Coord> Sub for $point<T> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($field: self.$field - rhs.$field),* }
+            }
+        }
+
+        impl<T: Coord> Mul<T> for $point<T> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: T) -> Self {
+                Self { $($field: self.$field * rhs),* }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $point<T> {
+            #[inline(always)]
+            fn add_assign(&mut self, rhs: Self) {
+                $(self.$field += rhs.$field;)*
+            }
+        }
+
+        impl<T: Coord> SubAssign for $point<T> {
+            #[inline(always)]
+            fn sub_assign(&mut self, rhs: Self) {
+                $(self.$field -= rhs.$field;)*
+            }
+        }
+    };
+}
+
+impl_point_ops!(Point2D { x, y });
+impl_point_ops!(Point3D { x, y, z });
+
+/// An axis-aligned rectangle with inclusive integer bounds.
+#[derive(Debug, Default, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct Rect2D<T = i64> {
+    pub min_x: T,
+    pub min_y: T,
+    pub max_x: T,
+    pub max_y: T,
+}
+
+impl<T: Coord> Rect2D<T> {
+    #[inline(always)]
+    pub fn from_corner_pair(corners: (&Point2D<T>, &Point2D<T>)) -> Self {
+        let (p1, p2) = corners;
+        Self::from_corners(p1, p2)
+    }
+
+    #[inline(always)]
+    pub fn from_corners(p1: &Point2D<T>, p2: &Point2D<T>) -> Self {
+        Self {
+            min_x: p1.x.min(p2.x),
+            min_y: p1.y.min(p2.y),
+            max_x: p1.x.max(p2.x),
+            max_y: p1.y.max(p2.y),
+        }
+    }
+
+    /// Returns the smallest rectangle containing all points.
+    pub fn bounding_box<'a, I>(points: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a Point2D<T>>,
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::from_corners(first, first), |r, p| {
+            r.union(&Self::from_corners(p, p))
+        }))
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> T {
+        self.max_x - self.min_x + T::ONE
+    }
+
+    #[inline(always)]
+    pub fn height(&self) -> T {
+        self.max_y - self.min_y + T::ONE
+    }
+
+    /// Returns the number of integer cells covered by the rectangle.
+    #[inline(always)]
+    pub fn area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, p: &Point2D<T>) -> bool {
+        (self.min_x..=self.max_x).contains(&p.x) && (self.min_y..=self.max_y).contains(&p.y)
+    }
+
+    /// Returns whether the interiors of the two rectangles overlap.
+    #[inline(always)]
+    pub fn intersects(&self, other: &Self) -> bool {
+        other.max_y > self.min_y
+            && other.min_y < self.max_y
+            && other.max_x > self.min_x
+            && other.min_x < self.max_x
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    #[inline(always)]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_metrics() {
+        let a = Point3D::<i64>::from_csv_str("162,817,812");
+        let b = Point3D::new(425, 690, 689);
+        assert_eq!(a.squared_distance(&b), 263 * 263 + 127 * 127 + 123 * 123);
+        assert_eq!(a.manhattan_distance(&b), 263 + 127 + 123);
+        assert_eq!(a.chebyshev_distance(&b), 263);
+        assert_eq!(b - a + a, b);
+        assert_eq!(a * 2, a + a);
+
+        let p = Point2D::<u32>::new(2, 9);
+        let q = Point2D::<u32>::from_csv_str("7,3");
+        assert_eq!(p.manhattan_distance(&q), 11);
+        assert_eq!(p.squared_distance(&q), 61);
+    }
+
+    #[test]
+    fn test_rect() {
+        let points = [Point2D::new(7, 1), Point2D::new(11, 7), Point2D::new(2, 5)];
+        let r = Rect2D::from_corners(&points[0], &points[1]);
+        assert_eq!((r.width(), r.height(), r.area()), (5, 7, 35));
+        assert!(r.contains(&Point2D::new(9, 5)) && !r.contains(&points[2]));
+
+        let bounds = Rect2D::bounding_box(&points).unwrap();
+        assert_eq!(
+            bounds,
+            Rect2D {
+                min_x: 2,
+                min_y: 1,
+                max_x: 11,
+                max_y: 7
+            }
+        );
+        assert!(bounds.intersects(&r));
+        assert!(!r.intersects(&Rect2D::from_corners(&points[1], &Point2D::new(20, 20))));
+    }
+}
@@ -0,0 +1,366 @@
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// The largest null space [`BitMatrix::min_weight_solution`] will enumerate, about a million
+/// combinations.
+pub const MAX_ENUMERABLE_NULLITY: usize = 20;
+
+/// Why [`BitMatrix::min_weight_solution`] gave no solution.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MinWeightError {
+    /// There is no solution, as shown by the certificate from [`BitMatrix::solve`].
+    Inconsistent(BitVector),
+    /// The null space has this many dimensions, more than [`MAX_ENUMERABLE_NULLITY`], so the
+    /// lightest solution wasn't searched for.
+    TooDegenerate(usize),
+}
+
+/// A fixed length vector over GF(2), packed 64 bits to a word.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct BitVector {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl BitVector {
+    /// Creates an all-zero vector.
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    pub fn get(&self, i: usize) -> bool {
+        debug_assert!(i < self.len);
+        self.words[i / WORD_BITS] >> (i % WORD_BITS) & 1 == 1
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, i: usize, value: bool) {
+        debug_assert!(i < self.len);
+        let bit = 1 << (i % WORD_BITS);
+        match value {
+            true => self.words[i / WORD_BITS] |= bit,
+            false => self.words[i / WORD_BITS] &= !bit,
+        }
+    }
+
+    /// Returns the Hamming weight.
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns the indices of the set bits in ascending order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * WORD_BITS + bit
+                })
+            })
+        })
+    }
+
+    #[inline(always)]
+    pub fn xor_assign(&mut self, other: &Self) {
+        debug_assert_eq!(self.len, other.len);
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a ^= b;
+        }
+    }
+}
+
+impl FromIterator<bool> for BitVector {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let bits: Vec<bool> = iter.into_iter().collect();
+        let mut vector = Self::new(bits.len());
+        for (i, _) in bits.iter().enumerate().filter(|(_, b)| **b) {
+            vector.set(i, true);
+        }
+        vector
+    }
+}
+
+/// A dense row-major matrix over GF(2), where adding rows is a word-wise XOR.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix {
+    /// Creates an all-zero matrix.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        let stride = cols.div_ceil(WORD_BITS);
+        Self {
+            rows,
+            cols,
+            stride,
+            words: vec![0; rows * stride],
+        }
+    }
+
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[inline(always)]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    #[inline(always)]
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        debug_assert!(row < self.rows && col < self.cols);
+        self.words[row * self.stride + col / WORD_BITS] >> (col % WORD_BITS) & 1 == 1
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        debug_assert!(row < self.rows && col < self.cols);
+        let word = &mut self.words[row * self.stride + col / WORD_BITS];
+        let bit = 1 << (col % WORD_BITS);
+        match value {
+            true => *word |= bit,
+            false => *word &= !bit,
+        }
+    }
+
+    /// Adds row `src` into row `dst`.
+    #[inline(always)]
+    fn xor_row(&mut self, dst: usize, src: usize) {
+        let (lo, hi) = (dst.min(src) * self.stride, dst.max(src) * self.stride);
+        let (head, tail) = self.words.split_at_mut(hi);
+        let (a, b) = (&mut head[lo..lo + self.stride], &mut tail[..self.stride]);
+        let (dst, src) = if dst < src { (a, b) } else { (b, a) };
+        for (d, s) in dst.iter_mut().zip(src.iter()) {
+            *d ^= s;
+        }
+    }
+
+    #[inline(always)]
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for k in 0..self.stride {
+            self.words.swap(a * self.stride + k, b * self.stride + k);
+        }
+    }
+
+    /// Reduces to reduced row echelon form, choosing pivots only from the first `pivot_cols`
+    /// columns so that any further columns just record the row operations. Returns the pivot
+    /// column of each leading row.
+    fn reduce(&mut self, pivot_cols: usize) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for col in 0..pivot_cols {
+            let r = pivots.len();
+            let Some(p) = (r..self.rows).find(|&i| self.get(i, col)) else {
+                continue;
+            };
+            if p != r {
+                self.swap_rows(p, r);
+            }
+            for i in 0..self.rows {
+                if i != r && self.get(i, col) {
+                    self.xor_row(i, r);
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    /// Reduces to reduced row echelon form in place, returning the pivot columns.
+    #[inline(always)]
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        self.reduce(self.cols)
+    }
+
+    #[inline(always)]
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    /// Returns a basis of the vectors `x` with `self * x = 0`, one per free column.
+    pub fn null_space(&self) -> Vec<BitVector> {
+        let mut reduced = self.clone();
+        let pivots = reduced.row_reduce();
+        reduced.null_space_of_reduced(self.cols, &pivots)
+    }
+
+    /// Reads the null space basis over the first `cols` columns of a reduced matrix.
+    fn null_space_of_reduced(&self, cols: usize, pivots: &[usize]) -> Vec<BitVector> {
+        let mut is_pivot = vec![false; cols];
+        pivots.iter().for_each(|&p| is_pivot[p] = true);
+
+        (0..cols)
+            .filter(|&f| !is_pivot[f])
+            .map(|f| {
+                let mut v = BitVector::new(cols);
+                v.set(f, true);
+                for (row, &p) in pivots.iter().enumerate() {
+                    v.set(p, self.get(row, f));
+                }
+                v
+            })
+            .collect()
+    }
+
+    /// Returns a solution of `self * x = b` with every free variable zero, and a basis of the null
+    /// space which generates every other solution.
+    ///
+    /// If there is no solution, returns a certificate instead: a set of rows which sum to zero
+    /// while their entries of `b` sum to one.
+    pub fn solve(&self, b: &BitVector) -> Result<(BitVector, Vec<BitVector>), BitVector> {
+        assert_eq!(
+            b.len(),
+            self.rows,
+            "Right hand side does not match the row count"
+        );
+
+        // Reduce [A | b | I], so the identity block tracks which rows each result row combines.
+        let (cols, rows) = (self.cols, self.rows);
+        let mut augmented = BitMatrix::new(rows, cols + 1 + rows);
+        for row in 0..rows {
+            for col in (0..cols).filter(|&col| self.get(row, col)) {
+                augmented.set(row, col, true);
+            }
+            augmented.set(row, cols, b.get(row));
+            augmented.set(row, cols + 1 + row, true);
+        }
+        let pivots = augmented.reduce(cols);
+
+        if let Some(row) = (pivots.len()..rows).find(|&row| augmented.get(row, cols)) {
+            return Err((0..rows)
+                .map(|i| augmented.get(row, cols + 1 + i))
+                .collect());
+        }
+
+        let mut x = BitVector::new(cols);
+        for (row, &p) in pivots.iter().enumerate() {
+            x.set(p, augmented.get(row, cols));
+        }
+        Ok((x, augmented.null_space_of_reduced(cols, &pivots)))
+    }
+
+    /// Returns a solution of `self * x = b` with the fewest set bits. Walks every combination of
+    /// the null space basis in Gray code order, so this is exponential in the nullity rather than
+    /// the number of columns, and gives up if the nullity exceeds [`MAX_ENUMERABLE_NULLITY`].
+    pub fn min_weight_solution(&self, b: &BitVector) -> Result<BitVector, MinWeightError> {
+        let (mut x, basis) = self.solve(b).map_err(MinWeightError::Inconsistent)?;
+        if basis.len() > MAX_ENUMERABLE_NULLITY {
+            return Err(MinWeightError::TooDegenerate(basis.len()));
+        }
+
+        let mut best = x.clone();
+        let mut best_weight = x.count_ones();
+        for gray in 1u64..1 << basis.len() {
+            x.xor_assign(&basis[gray.trailing_zeros() as usize]);
+            let weight = x.count_ones();
+            if weight < best_weight {
+                best_weight = weight;
+                best.clone_from(&x);
+            }
+        }
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: usize, columns: &[&[usize]]) -> BitMatrix {
+        let mut m = BitMatrix::new(rows, columns.len());
+        for (col, rows) in columns.iter().enumerate() {
+            rows.iter().for_each(|&row| m.set(row, col, true));
+        }
+        m
+    }
+
+    #[test]
+    fn test_bit_vector() {
+        let mut v: BitVector = (0..130).map(|i| i % 3 == 0).collect();
+        assert_eq!(v.count_ones(), 44);
+        v.set(129, false);
+        v.set(64, true);
+        assert!(v.get(0) && v.get(64) && !v.get(129));
+        assert_eq!(v.iter_ones().take(4).collect::<Vec<_>>(), [0, 3, 6, 9]);
+        v.xor_assign(&v.clone());
+        assert_eq!(v.count_ones(), 0);
+    }
+
+    #[test]
+    fn test_row_reduce_and_null_space() {
+        let m = matrix(4, &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        assert_eq!(m.rank(), 4);
+
+        let basis = m.null_space();
+        assert_eq!(basis.len(), 2);
+        for v in &basis {
+            for row in 0..m.rows() {
+                let dot = v.iter_ones().filter(|&c| m.get(row, c)).count();
+                assert_eq!(dot % 2, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_min_weight_solution() {
+        let m = matrix(4, &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        let target: BitVector = [false, true, true, false].into_iter().collect();
+        assert_eq!(m.min_weight_solution(&target).unwrap().count_ones(), 2);
+
+        // Rows 1 and 2 are toggled together, so they can't differ.
+        let singular = matrix(3, &[&[0], &[1, 2], &[0, 1, 2]]);
+        let target: BitVector = [false, true, false].into_iter().collect();
+        let Err(MinWeightError::Inconsistent(certificate)) = singular.min_weight_solution(&target)
+        else {
+            panic!("Expected a certificate");
+        };
+        assert_eq!(certificate.iter_ones().collect::<Vec<_>>(), [1, 2]);
+
+        // A chain of 200 lights where each button toggles a neighbouring pair, plus one which
+        // toggles both ends, needs the shorter way round the ring.
+        let n = 200;
+        let mut columns: Vec<Vec<usize>> = (0..n - 1).map(|i| vec![i, i + 1]).collect();
+        columns.push(vec![0, n - 1]);
+        let columns: Vec<&[usize]> = columns.iter().map(Vec::as_slice).collect();
+        let ring = matrix(n, &columns);
+        let target: BitVector = (0..n).map(|i| i == 10 || i == 190).collect();
+        assert_eq!(ring.min_weight_solution(&target).unwrap().count_ones(), 20);
+    }
+
+    #[test]
+    fn test_min_weight_solution_too_degenerate() {
+        // One light toggled by any of 22 buttons leaves 21 free combinations to choose from.
+        let target: BitVector = [true].into_iter().collect();
+        assert_eq!(
+            matrix(1, &[&[0][..]; 22]).min_weight_solution(&target),
+            Err(MinWeightError::TooDegenerate(21))
+        );
+        assert_eq!(
+            matrix(1, &[&[0][..]; 21])
+                .min_weight_solution(&target)
+                .unwrap()
+                .count_ones(),
+            1
+        );
+    }
+}
@@ -0,0 +1,788 @@
+use crate::dsu::DisjointSet;
+use crate::scan;
+use std::collections::HashMap;
+
+/// Dense node identifier within a [`Graph`].
+pub type Node = usize;
+
+/// The most waypoints [`Graph::count_paths`] will track, as its table doubles with each one.
+pub const MAX_WAYPOINTS: usize = 16;
+
+/// Why [`Graph::count_paths`] could not count the paths.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PathCountError {
+    /// A cycle reachable from the start, so there may be infinitely many paths.
+    Cycle(Vec<Node>),
+    /// More than [`MAX_WAYPOINTS`] waypoints were given.
+    TooManyWaypoints(usize),
+}
+
+/// A directed graph with interned node names and a compressed sparse row edge layout.
+#[derive(Debug, Clone, Default)]
+pub struct Graph<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, Node>,
+    offsets: Vec<usize>,
+    targets: Vec<Node>,
+    in_degree: Vec<usize>,
+}
+
+impl<'a> Graph<'a> {
+    /// Parses lines of the form "aaa: you hhh" into edges from the named node to each output.
+    pub fn from_adjacency_list(input: &'a str) -> Self {
+        let mut builder = GraphBuilder::default();
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let (name, outputs) = scan!(line, "{}: {}" => &str, Vec<&str>).unwrap();
+            let from = builder.intern(name);
+            for output in outputs {
+                let to = builder.intern(output);
+                builder.edges.push((from, to));
+            }
+        }
+        builder.build()
+    }
+
+    /// Builds a graph from named nodes and edges between their indices.
+    pub fn from_edges(names: Vec<&'a str>, edges: &[(Node, Node)]) -> Self {
+        let ids = names
+            .iter()
+            .enumerate()
+            .map(|(i, &name)| (name, i))
+            .collect();
+        GraphBuilder {
+            names,
+            ids,
+            edges: edges.to_vec(),
+        }
+        .build()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn num_edges(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Looks up a node by name.
+    #[inline(always)]
+    pub fn node(&self, name: &str) -> Option<Node> {
+        self.ids.get(name).copied()
+    }
+
+    #[inline(always)]
+    pub fn name(&self, node: Node) -> &'a str {
+        self.names[node]
+    }
+
+    #[inline(always)]
+    pub fn nodes(&self) -> std::ops::Range<Node> {
+        0..self.len()
+    }
+
+    #[inline(always)]
+    pub fn successors(&self, node: Node) -> &[Node] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// Returns every edge as `(from, to)`.
+    #[inline(always)]
+    pub fn edges(&self) -> impl Iterator<Item = (Node, Node)> + '_ {
+        self.nodes()
+            .flat_map(move |from| self.successors(from).iter().map(move |&to| (from, to)))
+    }
+
+    #[inline(always)]
+    pub fn out_degree(&self, node: Node) -> usize {
+        self.offsets[node + 1] - self.offsets[node]
+    }
+
+    #[inline(always)]
+    pub fn in_degree(&self, node: Node) -> usize {
+        self.in_degree[node]
+    }
+
+    /// Returns a graph with every edge reversed, keeping the same node ids.
+    pub fn reverse(&self) -> Self {
+        let edges: Vec<(Node, Node)> = self.edges().map(|(from, to)| (to, from)).collect();
+        GraphBuilder {
+            names: self.names.clone(),
+            ids: self.ids.clone(),
+            edges,
+        }
+        .build()
+    }
+
+    /// Returns the nodes in topological order, or a cycle if there is none.
+    pub fn topological_order(&self) -> Result<Vec<Node>, Vec<Node>> {
+        let mut remaining = self.in_degree.clone();
+        let mut order: Vec<Node> = self.nodes().filter(|&n| remaining[n] == 0).collect();
+
+        let mut i = 0;
+        while i < order.len() {
+            for &next in self.successors(order[i]) {
+                remaining[next] -= 1;
+                if remaining[next] == 0 {
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+
+        match order.len() == self.len() {
+            true => Ok(order),
+            false => Err(self
+                .find_cycle()
+                .expect("Unordered nodes must contain a cycle")),
+        }
+    }
+
+    /// Returns a cycle as a list of nodes, each with an edge to the next and the last with an
+    /// edge back to the first.
+    pub fn find_cycle(&self) -> Option<Vec<Node>> {
+        const UNVISITED: u8 = 0;
+        const ON_STACK: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNVISITED; self.len()];
+        let mut path: Vec<Node> = Vec::new();
+        let mut stack: Vec<(Node, usize)> = Vec::new();
+
+        for start in self.nodes() {
+            if state[start] != UNVISITED {
+                continue;
+            }
+            stack.push((start, 0));
+            path.push(start);
+            state[start] = ON_STACK;
+
+            while let Some(&mut (node, ref mut edge)) = stack.last_mut() {
+                match self.successors(node).get(*edge) {
+                    Some(&next) => {
+                        *edge += 1;
+                        match state[next] {
+                            UNVISITED => {
+                                state[next] = ON_STACK;
+                                stack.push((next, 0));
+                                path.push(next);
+                            }
+                            ON_STACK => {
+                                let begin = path.iter().rposition(|&n| n == next).unwrap();
+                                return Some(path[begin..].to_vec());
+                            }
+                            _ => {}
+                        }
+                    }
+                    None => {
+                        state[node] = DONE;
+                        stack.pop();
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the nodes reachable from `from` in topological order, starting with `from`, or a
+    /// cycle among them if there is one. Nodes which can't be reached are ignored.
+    pub fn topological_order_from(&self, from: Node) -> Result<Vec<Node>, Vec<Node>> {
+        const UNVISITED: u8 = 0;
+        const ON_STACK: u8 = 1;
+        const DONE: u8 = 2;
+
+        let mut state = vec![UNVISITED; self.len()];
+        let mut postorder: Vec<Node> = Vec::new();
+        let mut stack: Vec<(Node, usize)> = vec![(from, 0)];
+        state[from] = ON_STACK;
+
+        while let Some(&mut (node, ref mut edge)) = stack.last_mut() {
+            match self.successors(node).get(*edge) {
+                Some(&next) => {
+                    *edge += 1;
+                    match state[next] {
+                        UNVISITED => {
+                            state[next] = ON_STACK;
+                            stack.push((next, 0));
+                        }
+                        ON_STACK => {
+                            let begin = stack.iter().rposition(|&(n, _)| n == next).unwrap();
+                            return Err(stack[begin..].iter().map(|&(n, _)| n).collect());
+                        }
+                        _ => {}
+                    }
+                }
+                None => {
+                    state[node] = DONE;
+                    stack.pop();
+                    postorder.push(node);
+                }
+            }
+        }
+
+        postorder.reverse();
+        Ok(postorder)
+    }
+
+    /// Returns the number of paths from `from` to every node, or a cycle reachable from `from` if
+    /// there is one.
+    pub fn path_counts_from(&self, from: Node) -> Result<Vec<u128>, Vec<Node>> {
+        let order = self.topological_order_from(from)?;
+        let mut counts = vec![0u128; self.len()];
+        counts[from] = 1;
+        for &node in &order {
+            for &next in self.successors(node) {
+                counts[next] += counts[node];
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Returns the weakly connected components, each in ascending order.
+    pub fn weak_components(&self) -> Vec<Vec<Node>> {
+        let mut components = DisjointSet::new(self.len());
+        for (from, to) in self.edges() {
+            components.union(from, to);
+        }
+        components.components()
+    }
+
+    /// Counts the paths from `from` to `to` which pass through every node in `must_visit`, in
+    /// any order. Uses a DP over the topological order of the nodes reachable from `from` and
+    /// visited-waypoint bitmasks, so the cost is O((V + E) * 2^k) for `k` waypoints, at most
+    /// [`MAX_WAYPOINTS`]. Fails if a cycle is reachable from `from`.
+    pub fn count_paths(
+        &self,
+        from: Node,
+        to: Node,
+        must_visit: &[Node],
+    ) -> Result<u128, PathCountError> {
+        if must_visit.len() > MAX_WAYPOINTS {
+            return Err(PathCountError::TooManyWaypoints(must_visit.len()));
+        }
+        let order = self
+            .topological_order_from(from)
+            .map_err(PathCountError::Cycle)?;
+
+        let mut waypoint_bit = vec![0usize; self.len()];
+        for (i, &node) in must_visit.iter().enumerate() {
+            waypoint_bit[node] |= 1 << i;
+        }
+        let masks = 1usize << must_visit.len();
+        let full = masks - 1;
+
+        // ways[position[node] * masks + mask] counts paths from `from` to `node` visiting exactly
+        // `mask`, with rows only for the reachable nodes.
+        let mut position = vec![usize::MAX; self.len()];
+        for (i, &node) in order.iter().enumerate() {
+            position[node] = i;
+        }
+        if position[to] == usize::MAX {
+            return Ok(0);
+        }
+        let mut ways = vec![0u128; order.len() * masks];
+        ways[waypoint_bit[from]] = 1;
+
+        for (i, &node) in order.iter().enumerate() {
+            if node == to {
+                continue;
+            }
+            for mask in 0..masks {
+                let count = ways[i * masks + mask];
+                if count == 0 {
+                    continue;
+                }
+                for &next in self.successors(node) {
+                    ways[position[next] * masks + (mask | waypoint_bit[next])] += count;
+                }
+            }
+        }
+
+        Ok(ways[position[to] * masks + full])
+    }
+
+    /// Computes the dominator tree of every node reachable from `root`.
+    pub fn dominators(&self, root: Node) -> Dominators {
+        let reverse = self.reverse();
+        let idom = immediate_dominators(
+            self.len(),
+            root,
+            |n| self.successors(n).iter().copied(),
+            |n| reverse.successors(n).iter().copied(),
+        );
+        Dominators { root, idom }
+    }
+
+    /// Computes the post-dominator tree of every node which can reach `exit`.
+    #[inline(always)]
+    pub fn post_dominators(&self, exit: Node) -> Dominators {
+        self.reverse().dominators(exit)
+    }
+
+    /// Returns the edges which lie on every path from `from` to `to`, in path order, so removing
+    /// any one of them disconnects the two. Empty if `to` is unreachable.
+    pub fn cut_edges(&self, from: Node, to: Node) -> Vec<(Node, Node)> {
+        // Subdivide each edge `e` with a midpoint node `n + e`, which then dominates `to` exactly
+        // when the edge is on every path. Incoming midpoints are laid out like `offsets` so that
+        // neither neighbour lookup allocates.
+        let n = self.len();
+        let mut sources = vec![0; self.num_edges()];
+        let mut incoming_offsets = vec![0; n + 1];
+        for node in self.nodes() {
+            for e in self.offsets[node]..self.offsets[node + 1] {
+                sources[e] = node;
+                incoming_offsets[self.targets[e] + 1] += 1;
+            }
+        }
+        for node in 0..n {
+            incoming_offsets[node + 1] += incoming_offsets[node];
+        }
+        let mut incoming = vec![0; self.num_edges()];
+        let mut filled = incoming_offsets.clone();
+        for (e, &target) in self.targets.iter().enumerate() {
+            incoming[filled[target]] = n + e;
+            filled[target] += 1;
+        }
+
+        let idom = immediate_dominators(
+            n + self.num_edges(),
+            from,
+            |v| match v < n {
+                true => n + self.offsets[v]..n + self.offsets[v + 1],
+                false => self.targets[v - n]..self.targets[v - n] + 1,
+            },
+            |v| match v < n {
+                true => incoming[incoming_offsets[v]..incoming_offsets[v + 1]]
+                    .iter()
+                    .copied(),
+                false => std::slice::from_ref(&sources[v - n]).iter().copied(),
+            },
+        );
+        let dominators = Dominators { root: from, idom };
+
+        let mut edges: Vec<(Node, Node)> = dominators
+            .dominators(to)
+            .filter(|&v| v >= n)
+            .map(|v| (sources[v - n], self.targets[v - n]))
+            .collect();
+        edges.reverse();
+        edges
+    }
+
+    /// Returns the bridges of the graph viewed as undirected, as their original directed edges.
+    #[inline(always)]
+    pub fn bridges(&self) -> Vec<(Node, Node)> {
+        self.lowlinks().0
+    }
+
+    /// Returns the articulation points of the graph viewed as undirected, in ascending order.
+    #[inline(always)]
+    pub fn articulation_points(&self) -> Vec<Node> {
+        self.lowlinks().1
+    }
+
+    /// Tarjan's lowlink search over the undirected view, returning bridges and articulation points.
+    fn lowlinks(&self) -> (Vec<(Node, Node)>, Vec<Node>) {
+        const UNVISITED: usize = usize::MAX;
+
+        // Undirected adjacency as `(neighbour, edge)` pairs, laid out like `offsets`.
+        let edges: Vec<(Node, Node)> = self.edges().collect();
+        let mut adjacent_offsets = vec![0; self.len() + 1];
+        for &(from, to) in &edges {
+            adjacent_offsets[from + 1] += 1;
+            adjacent_offsets[to + 1] += 1;
+        }
+        for node in self.nodes() {
+            adjacent_offsets[node + 1] += adjacent_offsets[node];
+        }
+        let mut adjacent = vec![(0, 0); 2 * edges.len()];
+        let mut filled = adjacent_offsets.clone();
+        for (e, &(from, to)) in edges.iter().enumerate() {
+            adjacent[filled[from]] = (to, e);
+            filled[from] += 1;
+            adjacent[filled[to]] = (from, e);
+            filled[to] += 1;
+        }
+
+        let mut discovered = vec![UNVISITED; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut is_articulation = vec![false; self.len()];
+        let mut bridges = Vec::new();
+        let mut time = 0;
+        // Each frame is a node, the edge it was reached by and its next adjacency index.
+        let mut stack: Vec<(Node, usize, usize)> = Vec::new();
+
+        for root in self.nodes() {
+            if discovered[root] != UNVISITED {
+                continue;
+            }
+            discovered[root] = time;
+            low[root] = time;
+            time += 1;
+
+            let mut root_children = 0;
+            stack.push((root, usize::MAX, adjacent_offsets[root]));
+            while let Some(&mut (node, parent_edge, ref mut next)) = stack.last_mut() {
+                if *next < adjacent_offsets[node + 1] {
+                    let (other, e) = adjacent[*next];
+                    *next += 1;
+                    if e == parent_edge {
+                        continue;
+                    }
+                    if discovered[other] == UNVISITED {
+                        discovered[other] = time;
+                        low[other] = time;
+                        time += 1;
+                        stack.push((other, e, adjacent_offsets[other]));
+                    } else {
+                        low[node] = low[node].min(discovered[other]);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                let Some(&(parent, _, _)) = stack.last() else {
+                    continue;
+                };
+                low[parent] = low[parent].min(low[node]);
+                if low[node] > discovered[parent] {
+                    bridges.push(edges[parent_edge]);
+                }
+                if parent == root {
+                    root_children += 1;
+                } else if low[node] >= discovered[parent] {
+                    is_articulation[parent] = true;
+                }
+            }
+            is_articulation[root] = root_children > 1;
+        }
+
+        bridges.sort_unstable();
+        let articulation_points = self.nodes().filter(|&n| is_articulation[n]).collect();
+        (bridges, articulation_points)
+    }
+}
+
+/// A dominator tree: node `a` dominates `b` if every path from the root to `b` passes through `a`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Dominators {
+    root: Node,
+    idom: Vec<Option<Node>>,
+}
+
+impl Dominators {
+    #[inline(always)]
+    pub fn root(&self) -> Node {
+        self.root
+    }
+
+    #[inline(always)]
+    pub fn is_reachable(&self, node: Node) -> bool {
+        node == self.root || self.idom[node].is_some()
+    }
+
+    /// Returns the closest strict dominator of `node`, or `None` for the root and unreachable nodes.
+    #[inline(always)]
+    pub fn immediate_dominator(&self, node: Node) -> Option<Node> {
+        self.idom[node]
+    }
+
+    /// Returns every dominator of `node`, from `node` itself up to the root. Empty if `node` is
+    /// unreachable.
+    pub fn dominators(&self, node: Node) -> impl Iterator<Item = Node> + '_ {
+        let start = self.is_reachable(node).then_some(node);
+        std::iter::successors(start, |&n| self.idom[n])
+    }
+
+    #[inline(always)]
+    pub fn dominates(&self, a: Node, b: Node) -> bool {
+        self.dominators(b).any(|n| n == a)
+    }
+}
+
+/// Cooper, Harvey and Kennedy's iterative dominator algorithm over reverse postorder.
+fn immediate_dominators<S, P>(
+    n: usize,
+    root: Node,
+    successors: impl Fn(Node) -> S,
+    predecessors: impl Fn(Node) -> P,
+) -> Vec<Option<Node>>
+where
+    S: IntoIterator<Item = Node>,
+    P: IntoIterator<Item = Node>,
+{
+    const UNVISITED: usize = usize::MAX;
+
+    // Number reachable nodes in postorder with an iterative depth-first search.
+    let mut postorder = vec![UNVISITED; n];
+    let mut order = Vec::new();
+    let mut visited = vec![false; n];
+    let mut stack = vec![(root, successors(root).into_iter())];
+    visited[root] = true;
+    while let Some((node, next)) = stack.last_mut() {
+        match next.find(|&s| !visited[s]) {
+            Some(s) => {
+                visited[s] = true;
+                stack.push((s, successors(s).into_iter()));
+            }
+            None => {
+                postorder[*node] = order.len();
+                order.push(*node);
+                stack.pop();
+            }
+        }
+    }
+
+    let mut idom = vec![UNVISITED; n];
+    idom[root] = root;
+    let intersect = |idom: &[usize], mut a: Node, mut b: Node| {
+        while a != b {
+            while postorder[a] < postorder[b] {
+                a = idom[a];
+            }
+            while postorder[b] < postorder[a] {
+                b = idom[b];
+            }
+        }
+        a
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &node in order.iter().rev().skip(1) {
+            let new_idom = predecessors(node)
+                .into_iter()
+                .filter(|&p| idom[p] != UNVISITED)
+                .reduce(|a, b| intersect(&idom, a, b))
+                .unwrap();
+            if idom[node] != new_idom {
+                idom[node] = new_idom;
+                changed = true;
+            }
+        }
+    }
+
+    idom[root] = UNVISITED;
+    idom.into_iter()
+        .map(|d| (d != UNVISITED).then_some(d))
+        .collect()
+}
+
+#[derive(Default)]
+struct GraphBuilder<'a> {
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, Node>,
+    edges: Vec<(Node, Node)>,
+}
+
+impl<'a> GraphBuilder<'a> {
+    #[inline(always)]
+    fn intern(&mut self, name: &'a str) -> Node {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.names.len() - 1
+        })
+    }
+
+    fn build(self) -> Graph<'a> {
+        let n = self.names.len();
+        let mut offsets = vec![0; n + 1];
+        let mut in_degree = vec![0; n];
+        for &(from, to) in &self.edges {
+            offsets[from + 1] += 1;
+            in_degree[to] += 1;
+        }
+        for i in 0..n {
+            offsets[i + 1] += offsets[i];
+        }
+
+        let mut next = offsets.clone();
+        let mut targets = vec![0; self.edges.len()];
+        for &(from, to) in &self.edges {
+            targets[next[from]] = to;
+            next[from] += 1;
+        }
+
+        Graph {
+            names: self.names,
+            ids: self.ids,
+            offsets,
+            targets,
+            in_degree,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    const SAMPLE: &str = indoc! {"
+        aaa: you hhh
+        you: bbb ccc
+        bbb: ddd eee
+        ccc: ddd eee fff
+        ddd: ggg
+        eee: out
+        fff: out
+        ggg: out
+        hhh: ccc fff iii
+        iii: out
+    "};
+
+    #[test]
+    fn test_from_adjacency_list() {
+        let graph = Graph::from_adjacency_list(SAMPLE);
+        assert_eq!(graph.len(), 11);
+        assert_eq!(graph.num_edges(), 17);
+
+        let ccc = graph.node("ccc").unwrap();
+        let names: Vec<_> = graph
+            .successors(ccc)
+            .iter()
+            .map(|&n| graph.name(n))
+            .collect();
+        assert_eq!(names, ["ddd", "eee", "fff"]);
+        assert_eq!(graph.out_degree(ccc), 3);
+        assert_eq!(graph.in_degree(ccc), 2);
+
+        let out = graph.node("out").unwrap();
+        assert_eq!(graph.out_degree(out), 0);
+        assert_eq!(graph.reverse().out_degree(out), 4);
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = Graph::from_adjacency_list(SAMPLE);
+        let order = graph.topological_order().unwrap();
+        let mut position = vec![0; graph.len()];
+        for (i, &n) in order.iter().enumerate() {
+            position[n] = i;
+        }
+        assert!(
+            graph
+                .edges()
+                .all(|(from, to)| position[from] < position[to])
+        );
+
+        let cyclic = Graph::from_adjacency_list("a: b\nb: c d\nc: e\nd: b\n");
+        let cycle = cyclic.topological_order().unwrap_err();
+        let names: Vec<_> = cycle.iter().map(|&n| cyclic.name(n)).collect();
+        assert_eq!(names, ["b", "d"]);
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph = Graph::from_adjacency_list(indoc! {"
+            svr: aaa bbb
+            aaa: fft
+            fft: ccc
+            bbb: tty
+            tty: ccc
+            ccc: ddd eee
+            ddd: hub
+            hub: fff
+            eee: dac
+            dac: fff
+            fff: ggg hhh
+            ggg: out
+            hhh: out
+        "});
+        let node = |name| graph.node(name).unwrap();
+        let (svr, out, dac, fft) = (node("svr"), node("out"), node("dac"), node("fft"));
+
+        assert_eq!(graph.count_paths(svr, out, &[]), Ok(8));
+        assert_eq!(graph.count_paths(svr, out, &[dac]), Ok(4));
+        assert_eq!(graph.count_paths(svr, out, &[dac, fft]), Ok(2));
+        assert_eq!(graph.count_paths(svr, out, &[fft, dac]), Ok(2));
+        assert_eq!(graph.count_paths(dac, fft, &[]), Ok(0));
+        assert_eq!(graph.count_paths(out, out, &[]), Ok(1));
+        assert_eq!(
+            graph.count_paths(svr, out, &[out; 17]),
+            Err(PathCountError::TooManyWaypoints(17))
+        );
+
+        // A cycle which can't be reached from the start doesn't matter, but one which can does.
+        let cyclic = Graph::from_adjacency_list("a: b c\nb: d\nc: d\nx: y\ny: x b\n");
+        let node = |name| cyclic.node(name).unwrap();
+        assert_eq!(
+            cyclic.count_paths(node("a"), node("d"), &[node("c")]),
+            Ok(1)
+        );
+        assert_eq!(cyclic.path_counts_from(node("a")).unwrap()[node("d")], 2);
+        assert!(matches!(
+            cyclic.count_paths(node("x"), node("d"), &[]),
+            Err(PathCountError::Cycle(_))
+        ));
+
+        let counts = graph.path_counts_from(svr).unwrap();
+        assert_eq!((counts[fft], counts[dac], counts[out]), (1, 2, 8));
+        assert_eq!(graph.weak_components().len(), 1);
+    }
+
+    #[test]
+    fn test_dominators() {
+        let graph = Graph::from_adjacency_list(SAMPLE);
+        let node = |name| graph.node(name).unwrap();
+        let chain = |dominators: &Dominators, name| -> Vec<&str> {
+            dominators
+                .dominators(node(name))
+                .map(|n| graph.name(n))
+                .collect()
+        };
+
+        let dominators = graph.dominators(node("you"));
+        assert_eq!(chain(&dominators, "out"), ["out", "you"]);
+        assert_eq!(
+            dominators.immediate_dominator(node("ggg")),
+            Some(node("ddd"))
+        );
+        assert!(!dominators.is_reachable(node("hhh")));
+        assert!(dominators.dominates(node("ddd"), node("ggg")));
+
+        let post_dominators = graph.post_dominators(node("out"));
+        assert_eq!(chain(&post_dominators, "bbb"), ["bbb", "out"]);
+        assert_eq!(chain(&post_dominators, "ddd"), ["ddd", "ggg", "out"]);
+    }
+
+    #[test]
+    fn test_cut_edges_and_articulation() {
+        let graph = Graph::from_adjacency_list("a: b\nb: c d\nc: e\nd: e\ne: f\nf: g\n");
+        let edge_names = |edges: Vec<(Node, Node)>| -> Vec<(&str, &str)> {
+            edges
+                .into_iter()
+                .map(|(from, to)| (graph.name(from), graph.name(to)))
+                .collect()
+        };
+        let (a, g) = (graph.node("a").unwrap(), graph.node("g").unwrap());
+
+        assert_eq!(
+            edge_names(graph.cut_edges(a, g)),
+            [("a", "b"), ("e", "f"), ("f", "g")]
+        );
+        assert_eq!(edge_names(graph.cut_edges(g, a)), []);
+
+        let mut bridges = edge_names(graph.bridges());
+        bridges.sort_unstable();
+        assert_eq!(bridges, [("a", "b"), ("e", "f"), ("f", "g")]);
+
+        let mut points: Vec<_> = graph
+            .articulation_points()
+            .into_iter()
+            .map(|n| graph.name(n))
+            .collect();
+        points.sort_unstable();
+        assert_eq!(points, ["b", "e", "f"]);
+    }
+}
@@ -0,0 +1,518 @@
+/// Why a system has no non-negative integer solution.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Infeasibility {
+    /// Weighting each row by these multipliers cancels every coefficient but not the targets, so
+    /// there is no solution at all.
+    Inconsistent(Vec<i64>),
+    /// This row has only non-negative coefficients but a negative target.
+    NegativeTarget(usize),
+    /// There are solutions, but none in non-negative integers.
+    NoNaturalSolution,
+}
+
+/// The outcome of solving a min-sum system.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Solution {
+    Optimal(Vec<i64>),
+    Infeasible(Infeasibility),
+    /// A free variable is not bounded by any row, and no solution turned up while searching ever
+    /// larger totals before the search space grew too big to enumerate.
+    Undecided,
+}
+
+/// A solver was given a system outside the class it handles, so another should be tried.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Unsupported(pub &'static str);
+
+/// The most combinations of free variable values one search may cover.
+const ENUMERATION_LIMIT: f64 = (1 << 20) as f64;
+
+/// Finds a non-negative integer solution of `a x = b` minimising `Σx`, where `a` is given as rows
+/// of coefficients.
+///
+/// The system is brought into reduced row echelon form with exact fraction-free elimination, and
+/// the free variables left over are enumerated within the bounds implied by rows with only
+/// non-negative coefficients, pruning any branch which would force a pivot variable negative or
+/// cannot beat the best total so far. A free variable without such a bound can't exceed the
+/// total, so the search is repeated with a doubling limit on the total until a solution appears.
+pub fn min_sum_solution(a: &[Vec<i64>], b: &[i64]) -> Solution {
+    let rows = a.len();
+    let cols = a.first().map_or(0, Vec::len);
+    assert_eq!(rows, b.len(), "Row count does not match the targets");
+
+    // A variable can't exceed any target it contributes to in a row without negative coefficients,
+    // and one which appears in no row at all is best left at zero.
+    let non_negative: Vec<bool> = a.iter().map(|row| row.iter().all(|&c| c >= 0)).collect();
+    if let Some(row) = (0..rows).find(|&i| non_negative[i] && b[i] < 0) {
+        return Solution::Infeasible(Infeasibility::NegativeTarget(row));
+    }
+    let bounds: Vec<Option<i64>> = (0..cols)
+        .map(|j| match (0..rows).all(|i| a[i][j] == 0) {
+            true => Some(0),
+            false => (0..rows)
+                .filter(|&i| non_negative[i] && a[i][j] > 0)
+                .map(|i| b[i] / a[i][j])
+                .min(),
+        })
+        .collect();
+
+    let reduced = match Reduced::new(a, b) {
+        Ok(reduced) => reduced,
+        Err(multipliers) => return Solution::Infeasible(Infeasibility::Inconsistent(multipliers)),
+    };
+    let mut total_limit = b.iter().map(|t| t.abs()).max().unwrap_or(0).max(1);
+    let values = loop {
+        let free_bounds: Vec<i64> = reduced
+            .free
+            .iter()
+            .map(|&f| bounds[f].unwrap_or(total_limit))
+            .collect();
+        let limited = reduced.free.iter().any(|&f| bounds[f].is_none());
+        let combinations: f64 = free_bounds.iter().map(|&b| (b + 1) as f64).product();
+        if limited && combinations > ENUMERATION_LIMIT {
+            return Solution::Undecided;
+        }
+
+        let mut search = Search::new(&reduced, free_bounds, limited.then_some(total_limit));
+        search.descend(0);
+        match search.best_values {
+            Some(values) => break values,
+            None if limited => total_limit *= 2,
+            None => return Solution::Infeasible(Infeasibility::NoNaturalSolution),
+        }
+    };
+
+    let mut x = vec![0; cols];
+    for (&col, &value) in reduced.free.iter().zip(&values) {
+        x[col] = value;
+    }
+    for pivot in &reduced.pivots {
+        let residual = pivot.rhs
+            - pivot
+                .free_coeffs
+                .iter()
+                .zip(&values)
+                .map(|(&c, &v)| c * v)
+                .sum::<i64>();
+        x[pivot.col] = residual / pivot.coeff;
+    }
+    Solution::Optimal(x)
+}
+
+/// Checks that `x` is a non-negative solution of `a x = b`, for backends whose answers need
+/// verifying.
+pub fn is_natural_solution(a: &[Vec<i64>], b: &[i64], x: &[i64]) -> bool {
+    x.iter().all(|&v| v >= 0)
+        && a.iter().zip(b).all(|(row, &target)| {
+            row.len() == x.len() && row.iter().zip(x).map(|(&c, &v)| c * v).sum::<i64>() == target
+        })
+}
+
+/// Explains why `a x = b` has no non-negative integer solution, for backends which only know that
+/// there is none.
+pub fn certify_infeasible(a: &[Vec<i64>], b: &[i64]) -> Infeasibility {
+    let negative = (0..a.len()).find(|&i| b[i] < 0 && a[i].iter().all(|&c| c >= 0));
+    if let Some(row) = negative {
+        return Infeasibility::NegativeTarget(row);
+    }
+    match Reduced::new(a, b) {
+        Ok(_) => Infeasibility::NoNaturalSolution,
+        Err(multipliers) => Infeasibility::Inconsistent(multipliers),
+    }
+}
+
+/// Finds the same optimum as [`min_sum_solution`] by branching directly on the variables, for
+/// systems with only non-negative coefficients. Each step branches on a variable of the unmet row
+/// with the fewest unfixed variables, and prunes with the bound that every row still needs at
+/// least its remaining target over its largest unfixed coefficient.
+#[cfg(feature = "branch-and-bound")]
+pub fn min_sum_branch_and_bound(a: &[Vec<i64>], b: &[i64]) -> Result<Solution, Unsupported> {
+    let cols = a.first().map_or(0, Vec::len);
+    assert_eq!(a.len(), b.len(), "Row count does not match the targets");
+    if a.iter().flatten().any(|&c| c < 0) {
+        return Err(Unsupported("negative coefficients"));
+    }
+
+    let mut search = BranchAndBound {
+        a,
+        remaining: b.to_vec(),
+        x: vec![0; cols],
+        fixed: vec![false; cols],
+        total: 0,
+        best_total: i64::MAX,
+        best_x: None,
+    };
+    if b.iter().all(|&target| target >= 0) {
+        search.descend();
+    }
+
+    Ok(match search.best_x {
+        Some(x) => Solution::Optimal(x),
+        None => Solution::Infeasible(certify_infeasible(a, b)),
+    })
+}
+
+#[inline(always)]
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    (a, b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// An equation `coeff * x[col] + Σ free_coeffs[f] * x[free[f]] = rhs` of the reduced system.
+struct Pivot {
+    col: usize,
+    coeff: i64,
+    free_coeffs: Vec<i64>,
+    rhs: i64,
+}
+
+/// A system in reduced row echelon form, split into pivot equations and free columns.
+struct Reduced {
+    pivots: Vec<Pivot>,
+    free: Vec<usize>,
+}
+
+impl Reduced {
+    /// Eliminates with integer row operations. If the system is inconsistent, returns row
+    /// multipliers which cancel every coefficient but leave a non-zero target.
+    fn new(a: &[Vec<i64>], b: &[i64]) -> Result<Self, Vec<i64>> {
+        let (rows, cols) = (a.len(), a.first().map_or(0, Vec::len));
+
+        // Reduce [A | b | I], so the identity block tracks how each result row was combined.
+        let mut m: Vec<Vec<i64>> = a
+            .iter()
+            .zip(b)
+            .enumerate()
+            .map(|(i, (row, &rhs))| {
+                let identity = (0..rows).map(|k| (k == i) as i64);
+                row.iter().copied().chain([rhs]).chain(identity).collect()
+            })
+            .collect();
+
+        let mut pivot_cols = Vec::new();
+        for col in 0..cols {
+            let r = pivot_cols.len();
+            let Some(p) = (r..rows).find(|&i| m[i][col] != 0) else {
+                continue;
+            };
+            m.swap(r, p);
+            if m[r][col] < 0 {
+                m[r].iter_mut().for_each(|v| *v = -*v);
+            }
+
+            let pivot_row = m[r].clone();
+            for (i, row) in m.iter_mut().enumerate() {
+                if i == r || row[col] == 0 {
+                    continue;
+                }
+                let g = gcd(pivot_row[col], row[col]);
+                let (scale, factor) = (pivot_row[col] / g, row[col] / g);
+                for (v, &p) in row.iter_mut().zip(&pivot_row) {
+                    *v = *v * scale - p * factor;
+                }
+                let g = row.iter().fold(0, |g, &v| gcd(g, v));
+                if g > 1 {
+                    row.iter_mut().for_each(|v| *v /= g);
+                }
+            }
+            pivot_cols.push(col);
+        }
+
+        if let Some(row) = m[pivot_cols.len()..].iter().find(|row| row[cols] != 0) {
+            return Err(row[cols + 1..].to_vec());
+        }
+
+        let free: Vec<usize> = (0..cols).filter(|c| !pivot_cols.contains(c)).collect();
+        let pivots = pivot_cols
+            .iter()
+            .zip(&m)
+            .map(|(&col, row)| Pivot {
+                col,
+                coeff: row[col],
+                free_coeffs: free.iter().map(|&f| row[f]).collect(),
+                rhs: row[cols],
+            })
+            .collect();
+        Ok(Self { pivots, free })
+    }
+}
+
+/// Depth-first enumeration of the free variables. Objective values are scaled by the least common
+/// multiple of the pivot coefficients so that every bound stays an exact integer.
+struct Search<'a> {
+    reduced: &'a Reduced,
+    bounds: Vec<i64>,
+    /// Scaled objective contribution of each free variable.
+    weights: Vec<i128>,
+    /// Per level, the most each pivot residual can still grow from the unassigned variables.
+    slack: Vec<Vec<i64>>,
+    /// Per level, the most the scaled objective can still shrink from the unassigned variables.
+    lowest: Vec<i128>,
+    values: Vec<i64>,
+    residuals: Vec<i64>,
+    objective: i128,
+    best_objective: i128,
+    best_values: Option<Vec<i64>>,
+}
+
+impl<'a> Search<'a> {
+    /// Searches within `bounds` on the free variables, and for totals of at most `max_total` if
+    /// given.
+    fn new(reduced: &'a Reduced, bounds: Vec<i64>, max_total: Option<i64>) -> Self {
+        let scale = reduced
+            .pivots
+            .iter()
+            .fold(1, |l, p| l / gcd(l, p.coeff) * p.coeff) as i128;
+        let n = bounds.len();
+
+        let weights: Vec<i128> = (0..n)
+            .map(|f| {
+                scale
+                    - reduced
+                        .pivots
+                        .iter()
+                        .map(|p| scale / p.coeff as i128 * p.free_coeffs[f] as i128)
+                        .sum::<i128>()
+            })
+            .collect();
+        let objective = reduced
+            .pivots
+            .iter()
+            .map(|p| scale / p.coeff as i128 * p.rhs as i128)
+            .sum();
+
+        let mut slack = vec![vec![0; reduced.pivots.len()]; n + 1];
+        let mut lowest = vec![0; n + 1];
+        for f in (0..n).rev() {
+            for (k, p) in reduced.pivots.iter().enumerate() {
+                slack[f][k] = slack[f + 1][k] + (-p.free_coeffs[f] * bounds[f]).max(0);
+            }
+            lowest[f] = lowest[f + 1] + (weights[f] * bounds[f] as i128).min(0);
+        }
+
+        Self {
+            reduced,
+            bounds,
+            weights,
+            slack,
+            lowest,
+            values: vec![0; n],
+            residuals: reduced.pivots.iter().map(|p| p.rhs).collect(),
+            objective,
+            best_objective: max_total.map_or(i128::MAX, |t| t as i128 * scale + 1),
+            best_values: None,
+        }
+    }
+
+    #[inline(always)]
+    fn is_promising(&self, level: usize) -> bool {
+        self.objective + self.lowest[level] < self.best_objective
+            && self
+                .residuals
+                .iter()
+                .zip(&self.slack[level])
+                .all(|(&r, &s)| r + s >= 0)
+    }
+
+    fn descend(&mut self, level: usize) {
+        if !self.is_promising(level) {
+            return;
+        }
+
+        if level == self.values.len() {
+            let integral = self
+                .residuals
+                .iter()
+                .zip(&self.reduced.pivots)
+                .all(|(&r, p)| r % p.coeff == 0);
+            if integral {
+                self.best_objective = self.objective;
+                self.best_values = Some(self.values.clone());
+            }
+            return;
+        }
+
+        for value in 0..=self.bounds[level] {
+            self.assign(level, value);
+            self.descend(level + 1);
+        }
+        self.assign(level, 0);
+    }
+
+    #[inline(always)]
+    fn assign(&mut self, level: usize, value: i64) {
+        let delta = value - self.values[level];
+        self.values[level] = value;
+        self.objective += self.weights[level] * delta as i128;
+        for (r, p) in self.residuals.iter_mut().zip(&self.reduced.pivots) {
+            *r -= p.free_coeffs[level] * delta;
+        }
+    }
+}
+
+#[cfg(feature = "branch-and-bound")]
+struct BranchAndBound<'a> {
+    a: &'a [Vec<i64>],
+    remaining: Vec<i64>,
+    x: Vec<i64>,
+    fixed: Vec<bool>,
+    total: i64,
+    best_total: i64,
+    best_x: Option<Vec<i64>>,
+}
+
+#[cfg(feature = "branch-and-bound")]
+impl BranchAndBound<'_> {
+    fn descend(&mut self) {
+        // Find the lower bound and the unmet row with the fewest ways left to meet it.
+        let mut bound = 0;
+        let mut branch: Option<(usize, usize)> = None;
+        for (i, &remaining) in self.remaining.iter().enumerate() {
+            if remaining == 0 {
+                continue;
+            }
+            let (count, largest) = (0..self.x.len())
+                .filter(|&j| !self.fixed[j] && self.a[i][j] > 0)
+                .fold((0, 0), |(count, largest), j| {
+                    (count + 1, largest.max(self.a[i][j]))
+                });
+            if count == 0 {
+                return;
+            }
+            bound = bound.max((remaining + largest - 1) / largest);
+            if branch.is_none_or(|(_, fewest)| count < fewest) {
+                branch = Some((i, count));
+            }
+        }
+
+        let Some((i, count)) = branch else {
+            if self.total < self.best_total {
+                self.best_total = self.total;
+                self.best_x = Some(self.x.clone());
+            }
+            return;
+        };
+        if self.total + bound >= self.best_total {
+            return;
+        }
+
+        let j = (0..self.x.len())
+            .find(|&j| !self.fixed[j] && self.a[i][j] > 0)
+            .unwrap();
+        let most = (0..self.remaining.len())
+            .filter(|&k| self.a[k][j] > 0)
+            .map(|k| self.remaining[k] / self.a[k][j])
+            .min()
+            .unwrap();
+
+        // The last variable in a row must meet it exactly, otherwise try the largest first.
+        let values = match count {
+            1 if self.remaining[i] % self.a[i][j] == 0 => {
+                let forced = self.remaining[i] / self.a[i][j];
+                forced..=forced.min(most)
+            }
+            1 => return,
+            _ => 0..=most,
+        };
+
+        self.fixed[j] = true;
+        for value in values.rev() {
+            self.apply(j, value);
+            self.descend();
+            self.apply(j, -value);
+        }
+        self.fixed[j] = false;
+    }
+
+    #[inline(always)]
+    fn apply(&mut self, j: usize, delta: i64) {
+        self.x[j] += delta;
+        self.total += delta;
+        for (remaining, row) in self.remaining.iter_mut().zip(self.a) {
+            *remaining -= row[j] * delta;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn button_matrix(counters: usize, buttons: &[&[usize]]) -> Vec<Vec<i64>> {
+        (0..counters)
+            .map(|i| buttons.iter().map(|b| b.contains(&i) as i64).collect())
+            .collect()
+    }
+
+    fn optimal(solution: Solution) -> Vec<i64> {
+        match solution {
+            Solution::Optimal(x) => x,
+            other => panic!("Expected an optimal solution, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_min_sum_solution() {
+        let a = button_matrix(4, &[&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]]);
+        let b = [3, 5, 4, 7];
+        let x = optimal(min_sum_solution(&a, &b));
+        assert_eq!(x.iter().sum::<i64>(), 10);
+        assert!(x.iter().all(|&v| v >= 0));
+        for (row, &target) in a.iter().zip(&b) {
+            assert_eq!(row.iter().zip(&x).map(|(a, x)| a * x).sum::<i64>(), target);
+        }
+
+        let a = button_matrix(
+            6,
+            &[&[0, 1, 2, 3, 4], &[0, 3, 4], &[0, 1, 2, 4, 5], &[1, 2]],
+        );
+        let x = optimal(min_sum_solution(&a, &[10, 11, 11, 5, 10, 5]));
+        assert_eq!(x.iter().sum::<i64>(), 11);
+
+        assert_eq!(optimal(min_sum_solution(&[vec![1, 0]], &[0])), [0, 0]);
+        let a = [vec![1, -1], vec![0, 1]];
+        assert_eq!(optimal(min_sum_solution(&a, &[2, 3])), [5, 3]);
+
+        // No row bounds either variable, but the total does once a solution is found.
+        assert_eq!(optimal(min_sum_solution(&[vec![1, -1]], &[0])), [0, 0]);
+        assert_eq!(optimal(min_sum_solution(&[vec![1, -1]], &[-3])), [0, 3]);
+        let a = [vec![1, -1, 0], vec![0, 1, -2]];
+        assert_eq!(optimal(min_sum_solution(&a, &[-5, 0])), [1, 6, 3]);
+    }
+
+    #[test]
+    fn test_min_sum_solution_infeasible() {
+        use Infeasibility::*;
+        use Solution::*;
+
+        assert_eq!(
+            min_sum_solution(&[vec![2]], &[3]),
+            Infeasible(NoNaturalSolution)
+        );
+        assert_eq!(
+            min_sum_solution(&[vec![0]], &[1]),
+            Infeasible(Inconsistent(vec![1]))
+        );
+        assert_eq!(
+            min_sum_solution(&[vec![1]], &[-1]),
+            Infeasible(NegativeTarget(0))
+        );
+        assert_eq!(min_sum_solution(&[vec![2, -2]], &[1]), Undecided);
+
+        let a = [vec![1, -1], vec![0, 1]];
+        assert!(is_natural_solution(&a, &[2, 3], &[5, 3]));
+        assert!(!is_natural_solution(&a, &[2, 3], &[5, 2]));
+        assert!(!is_natural_solution(&a, &[-1, -1], &[-2, -1]));
+
+        let a = [vec![1, 1], vec![1, 1]];
+        let b = [2, 3];
+        let Infeasible(Inconsistent(y)) = min_sum_solution(&a, &b) else {
+            panic!("Expected an inconsistency certificate");
+        };
+        assert!((0..2).all(|j| y[0] * a[0][j] + y[1] * a[1][j] == 0));
+        assert_ne!(y[0] * b[0] + y[1] * b[1], 0);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::geom::{Coord, Point3D};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A static, implicitly balanced k-d tree over 3D points. Results refer to points by their index
+/// in the slice the tree was built from, and ties in distance are broken by that index.
+#[derive(Debug, Clone)]
+pub struct KdTree<T = i64> {
+    nodes: Vec<(Point3D<T>, usize)>,
+    points: Vec<Point3D<T>>,
+}
+
+#[inline(always)]
+fn axis<T: Coord>(p: &Point3D<T>, depth: usize) -> T {
+    match depth % 3 {
+        0 => p.x,
+        1 => p.y,
+        _ => p.z,
+    }
+}
+
+impl<T: Coord> KdTree<T> {
+    pub fn new(points: &[Point3D<T>]) -> Self {
+        fn build<T: Coord>(nodes: &mut [(Point3D<T>, usize)], depth: usize) {
+            if nodes.len() <= 1 {
+                return;
+            }
+            let mid = nodes.len() / 2;
+            nodes.select_nth_unstable_by_key(mid, |(p, _)| axis(p, depth));
+            let (left, right) = nodes.split_at_mut(mid);
+            build(left, depth + 1);
+            build(&mut right[1..], depth + 1);
+        }
+
+        let mut nodes: Vec<(Point3D<T>, usize)> = points.iter().copied().zip(0..).collect();
+        build(&mut nodes, 0);
+        Self {
+            nodes,
+            points: points.to_vec(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the point with the given index.
+    #[inline(always)]
+    pub fn point(&self, index: usize) -> &Point3D<T> {
+        &self.points[index]
+    }
+
+    /// Returns up to `k` nearest points as `(squared distance, index)`, nearest first.
+    #[inline(always)]
+    pub fn nearest(&self, query: &Point3D<T>, k: usize) -> Vec<(T, usize)> {
+        self.nearest_filtered(query, k, |_| true)
+    }
+
+    /// Returns up to `k` nearest points whose index passes `filter`, nearest first.
+    pub fn nearest_filtered(
+        &self,
+        query: &Point3D<T>,
+        k: usize,
+        filter: impl Fn(usize) -> bool,
+    ) -> Vec<(T, usize)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search_nearest(0, self.nodes.len(), 0, query, k, &filter, &mut heap);
+        }
+        heap.into_sorted_vec()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search_nearest(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        query: &Point3D<T>,
+        k: usize,
+        filter: &impl Fn(usize) -> bool,
+        heap: &mut BinaryHeap<(T, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        let (p, index) = self.nodes[mid];
+        if filter(index) {
+            heap.push((p.squared_distance(query), index));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        let (q_axis, p_axis) = (axis(query, depth), axis(&p, depth));
+        let (near, far) = match q_axis < p_axis {
+            true => ((lo, mid), (mid + 1, hi)),
+            false => ((mid + 1, hi), (lo, mid)),
+        };
+        self.search_nearest(near.0, near.1, depth + 1, query, k, filter, heap);
+
+        let plane = T::abs_diff(q_axis, p_axis);
+        if heap.len() < k || plane * plane <= heap.peek().unwrap().0 {
+            self.search_nearest(far.0, far.1, depth + 1, query, k, filter, heap);
+        }
+    }
+
+    /// Returns all points within squared distance `radius_squared` as `(squared distance, index)`,
+    /// nearest first.
+    pub fn within_radius(&self, query: &Point3D<T>, radius_squared: T) -> Vec<(T, usize)> {
+        let mut found = Vec::new();
+        let mut stack = vec![(0, self.nodes.len(), 0)];
+        while let Some((lo, hi, depth)) = stack.pop() {
+            if lo >= hi {
+                continue;
+            }
+
+            let mid = (lo + hi) / 2;
+            let (p, index) = self.nodes[mid];
+            let distance = p.squared_distance(query);
+            if distance <= radius_squared {
+                found.push((distance, index));
+            }
+
+            let (q_axis, p_axis) = (axis(query, depth), axis(&p, depth));
+            let plane = T::abs_diff(q_axis, p_axis);
+            let in_reach = plane * plane <= radius_squared;
+            if q_axis < p_axis || in_reach {
+                stack.push((lo, mid, depth + 1));
+            }
+            if q_axis >= p_axis || in_reach {
+                stack.push((mid + 1, hi, depth + 1));
+            }
+        }
+        found.sort_unstable();
+        found
+    }
+
+    /// Returns an iterator over all pairs `(squared distance, i, j)` with `i < j`, closest first,
+    /// without materialising every pair.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, T> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbours: vec![Neighbours::default(); self.len()],
+            heap: BinaryHeap::with_capacity(self.len()),
+        };
+        for i in 0..self.len() {
+            pairs.push_next(i);
+        }
+        pairs
+    }
+}
+
+#[derive(Clone, Default)]
+struct Neighbours<T> {
+    found: Vec<(T, usize)>,
+    requested: usize,
+    next: usize,
+}
+
+/// Iterator produced by [`KdTree::closest_pairs`]. Each point lazily fetches its neighbours with a
+/// higher index in growing batches, and a heap merges those streams in ascending distance.
+pub struct ClosestPairs<'a, T> {
+    tree: &'a KdTree<T>,
+    neighbours: Vec<Neighbours<T>>,
+    heap: BinaryHeap<Reverse<(T, usize, usize)>>,
+}
+
+impl<T: Coord> ClosestPairs<'_, T> {
+    const INITIAL_BATCH: usize = 4;
+
+    /// Queues the next closest neighbour of `i`, fetching a larger batch if needed.
+    fn push_next(&mut self, i: usize) {
+        let neighbours = &mut self.neighbours[i];
+        if neighbours.next == neighbours.found.len() {
+            // A short batch means every neighbour has already been found.
+            if neighbours.found.len() < neighbours.requested {
+                return;
+            }
+            neighbours.requested = (neighbours.requested * 2).max(Self::INITIAL_BATCH);
+            neighbours.found =
+                self.tree
+                    .nearest_filtered(&self.tree.points[i], neighbours.requested, |j| j > i);
+        }
+
+        if let Some(&(distance, j)) = neighbours.found.get(neighbours.next) {
+            neighbours.next += 1;
+            self.heap.push(Reverse((distance, i, j)));
+        }
+    }
+}
+
+impl<T: Coord> Iterator for ClosestPairs<'_, T> {
+    type Item = (T, usize, usize);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(pair) = self.heap.pop()?;
+        self.push_next(pair.1);
+        Some(pair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points() -> Vec<Point3D> {
+        (0..200i64)
+            .map(|i| Point3D::new((i * 7919) % 101, (i * 104729) % 89, (i * 31) % 97))
+            .collect()
+    }
+
+    #[test]
+    fn test_nearest_and_radius() {
+        let points = points();
+        let tree = KdTree::new(&points);
+        let query = Point3D::new(50, 40, 30);
+
+        let mut brute: Vec<(i64, usize)> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.squared_distance(&query), i))
+            .collect();
+        brute.sort_unstable();
+
+        assert_eq!(tree.nearest(&query, 10), brute[..10]);
+        let within: Vec<_> = brute.iter().copied().filter(|&(d, _)| d <= 400).collect();
+        assert_eq!(tree.within_radius(&query, 400), within);
+    }
+
+    #[test]
+    fn test_closest_pairs() {
+        let points = points();
+        let tree = KdTree::new(&points);
+
+        let mut brute = Vec::new();
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                brute.push((points[i].squared_distance(&points[j]), i, j));
+            }
+        }
+        brute.sort_unstable();
+
+        let pairs: Vec<_> = tree.closest_pairs().collect();
+        assert_eq!(pairs.len(), brute.len());
+        assert!(pairs.iter().map(|p| p.0).eq(brute.iter().map(|p| p.0)));
+    }
+}
@@ -1,4 +1,24 @@
-use atoi::atoi;
+#![feature(portable_simd)]
+
+pub mod budget;
+pub mod compress;
+pub mod dot;
+pub mod dsu;
+pub mod exact_cover;
+pub mod geom;
+pub mod gf2;
+pub mod graph;
+pub mod ilp;
+pub mod kdtree;
+pub mod mst;
+pub mod parse;
+pub mod polygon;
+pub mod polyomino;
+pub mod ranges;
+pub mod scan;
+pub mod solver;
+
+use atoi::{FromRadix10SignedChecked, atoi};
 use memchr::{memchr, memchr_iter};
 use memmap2::Mmap;
 use std::alloc::System;
@@ -31,26 +51,93 @@ pub fn get_input_as_string() -> String {
     fs::read_to_string(get_input_filename()).expect("Could not open input file")
 }
 
-/// Gets the two different parts of the puzzle input as d
+#[inline]
+/// Returns whether the AOC_VERBOSE environment variable asks for diagnostics on stderr
+pub fn is_verbose() -> bool {
+    env::var_os("AOC_VERBOSE").is_some()
+}
+
+/// Gets the two different parts of the puzzle input as delimited by a blank line.
 #[inline(always)]
 pub fn split_input_parts(input: &str) -> (&str, &str) {
-    let bytes = input.as_bytes();
-    let length = bytes.len();
+    let [a, b] = split_sections(input);
+    (a, b)
+}
+
+/// Splits the puzzle input into exactly `N` blank-line separated sections.
+#[inline(always)]
+pub fn split_sections<const N: usize>(input: &str) -> [&str; N] {
+    let mut iter = sections(input);
+    let parts = std::array::from_fn(|_| {
+        iter.next()
+            .unwrap_or_else(|| panic!("Puzzle input has fewer than {N} sections"))
+    });
+    assert!(
+        iter.next().is_none(),
+        "Puzzle input has more than {N} sections"
+    );
+    parts
+}
+
+/// Returns an iterator over the blank-line separated sections of the puzzle input.
+#[inline(always)]
+pub fn sections(input: &str) -> Sections<'_> {
+    Sections { input, offset: 0 }
+}
+
+/// Iterator over blank-line separated sections, tolerating `\r\n` line endings and any number of
+/// leading or trailing blank lines. Sections are returned without their final line ending.
+pub struct Sections<'a> {
+    input: &'a str,
+    offset: usize,
+}
 
-    let mut i = 0;
-    while i < length {
-        if let Some(rel) = memchr(b'\n', unsafe { bytes.get_unchecked(i..) }) {
+impl<'a> Sections<'a> {
+    /// Returns the length of the line ending at `i` if the line starting at `i` is blank.
+    #[inline(always)]
+    fn blank_line_at(bytes: &[u8], i: usize) -> Option<usize> {
+        match bytes.get(i..) {
+            Some([b'\n', ..]) => Some(1),
+            Some([b'\r', b'\n', ..]) => Some(2),
+            Some([b'\r']) => Some(1),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Iterator for Sections<'a> {
+    type Item = &'a str;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.input.as_bytes();
+        let length = bytes.len();
+
+        while let Some(skip) = Self::blank_line_at(bytes, self.offset) {
+            self.offset += skip;
+        }
+        if self.offset >= length {
+            return None;
+        }
+
+        let start = self.offset;
+        let mut i = start;
+        let mut end = length;
+        while let Some(rel) = memchr(b'\n', unsafe { bytes.get_unchecked(i..) }) {
             let p = i + rel;
-            if p + 1 < length && unsafe { *bytes.get_unchecked(p + 1) } == b'\n' {
-                let a = unsafe { std::str::from_utf8_unchecked(bytes.get_unchecked(..p)) };
-                let b = unsafe { std::str::from_utf8_unchecked(bytes.get_unchecked(p + 2..)) };
-                return (a, b);
-            }
             i = p + 1;
+            if i >= length || Self::blank_line_at(bytes, i).is_some() {
+                end = p;
+                break;
+            }
         }
-    }
+        self.offset = i.max(end);
 
-    panic!("Puzzle input is not seperated into two parts");
+        if end > start && bytes[end - 1] == b'\r' {
+            end -= 1;
+        }
+        Some(unsafe { self.input.get_unchecked(start..end) })
+    }
 }
 
 #[inline(always)]
@@ -68,7 +155,7 @@ pub fn get_input_as_bytes() -> &'static [u8] {
 
 #[inline(always)]
 /// Parse lines of the form "1234567898-123456789"
-pub fn parse_range(range: &str) -> (i64, i64) {
+pub fn parse_range<T: FromRadix10SignedChecked>(range: &str) -> (T, T) {
     let (start, end) = range.split_once('-').unwrap();
     (
         atoi(start.as_bytes()).unwrap(),
@@ -280,3 +367,36 @@ impl Deref for GridPoint<'_> {
         &self.grid.bytes[self.offset]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let input = "a\nb\n\nc\n\n\nd\ne\n\n";
+        assert_eq!(sections(input).collect::<Vec<_>>(), ["a\nb", "c", "d\ne"]);
+
+        let input = "\r\na\r\nb\r\n\r\nc\r\n\r\n";
+        assert_eq!(sections(input).collect::<Vec<_>>(), ["a\r\nb", "c"]);
+
+        assert_eq!(sections("").count(), 0);
+        assert_eq!(sections("\n\n").count(), 0);
+        assert_eq!(sections("a").collect::<Vec<_>>(), ["a"]);
+    }
+
+    #[test]
+    fn test_split_sections() {
+        assert_eq!(
+            split_sections::<3>("1-2\n\n3\n4\n\n5\n"),
+            ["1-2", "3\n4", "5"]
+        );
+        assert_eq!(split_input_parts("1-2\n3-4\n\n5\n"), ("1-2\n3-4", "5"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_sections_too_few() {
+        split_sections::<3>("a\n\nb\n");
+    }
+}
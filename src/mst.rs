@@ -0,0 +1,108 @@
+use crate::geom::{Coord, Point3D};
+use rayon::prelude::*;
+
+/// Computes a minimum spanning tree of the complete graph on `n` vertices using the dense variant
+/// of Prim's algorithm, in O(n²) time and O(n) memory. Returns the `n - 1` tree edges as
+/// `(weight, i, j)` with `i < j`, in ascending weight order, which is the order Kruskal's
+/// algorithm would join them.
+pub fn prim_complete<T: Coord>(
+    n: usize,
+    weight: impl Fn(usize, usize) -> T,
+) -> Vec<(T, usize, usize)> {
+    prim(n, |remaining, latest| {
+        for entry in remaining.iter_mut() {
+            let w = weight(latest, entry.0);
+            if entry.2 == usize::MAX || w < entry.1 {
+                *entry = (entry.0, w, latest);
+            }
+        }
+    })
+}
+
+/// Parallel version of [`prim_complete`], worthwhile once `n` reaches the tens of thousands.
+pub fn par_prim_complete<T: Coord + Send + Sync>(
+    n: usize,
+    weight: impl Fn(usize, usize) -> T + Sync,
+) -> Vec<(T, usize, usize)> {
+    prim(n, |remaining, latest| {
+        remaining.par_iter_mut().for_each(|entry| {
+            let w = weight(latest, entry.0);
+            if entry.2 == usize::MAX || w < entry.1 {
+                *entry = (entry.0, w, latest);
+            }
+        });
+    })
+}
+
+/// Shared driver for the Prim variants. `remaining` holds `(vertex, best weight, best parent)` for
+/// every vertex not yet in the tree (with no parent as `usize::MAX`), and `relax` updates it after
+/// `latest` joins the tree.
+fn prim<T: Coord>(
+    n: usize,
+    mut relax: impl FnMut(&mut [(usize, T, usize)], usize),
+) -> Vec<(T, usize, usize)> {
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+    if n == 0 {
+        return edges;
+    }
+
+    let mut remaining: Vec<(usize, T, usize)> = (1..n).map(|v| (v, T::ZERO, usize::MAX)).collect();
+    let mut latest = 0;
+    while !remaining.is_empty() {
+        relax(&mut remaining, latest);
+
+        let (best, _) = remaining
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, e)| e.1)
+            .unwrap();
+        let (v, w, parent) = remaining.swap_remove(best);
+        edges.push((w, parent.min(v), parent.max(v)));
+        latest = v;
+    }
+
+    edges.sort_unstable();
+    edges
+}
+
+/// Computes the Euclidean minimum spanning tree of a point cloud, weighted by squared distance.
+#[inline(always)]
+pub fn euclidean_mst<T: Coord>(points: &[Point3D<T>]) -> Vec<(T, usize, usize)> {
+    prim_complete(points.len(), |i, j| points[i].squared_distance(&points[j]))
+}
+
+/// Parallel version of [`euclidean_mst`].
+#[inline(always)]
+pub fn par_euclidean_mst<T: Coord + Send + Sync>(points: &[Point3D<T>]) -> Vec<(T, usize, usize)> {
+    par_prim_complete(points.len(), |i, j| points[i].squared_distance(&points[j]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dsu::DisjointSet;
+    use crate::kdtree::KdTree;
+
+    #[test]
+    fn test_euclidean_mst() {
+        let points: Vec<Point3D> = (0..150i64)
+            .map(|i| Point3D::new((i * 7919) % 1009, (i * 104729) % 997, (i * 31) % 991))
+            .collect();
+
+        // Kruskal over every pair gives the same total weight and final joining edge.
+        let mut circuits = DisjointSet::new(points.len());
+        let kruskal: Vec<_> = KdTree::new(&points)
+            .closest_pairs()
+            .filter(|&(_, i, j)| circuits.union(i, j))
+            .collect();
+
+        let mst = euclidean_mst(&points);
+        assert_eq!(mst.len(), points.len() - 1);
+        assert_eq!(
+            mst.iter().map(|e| e.0).sum::<i64>(),
+            kruskal.iter().map(|e| e.0).sum::<i64>()
+        );
+        assert_eq!(mst.last().unwrap().0, kruskal.last().unwrap().0);
+        assert_eq!(par_euclidean_mst(&points), mst);
+    }
+}
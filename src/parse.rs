@@ -0,0 +1,234 @@
+use memchr::memchr;
+use std::simd::cmp::SimdPartialOrd;
+use std::simd::u8x64;
+
+const LANES: usize = 64;
+
+/// Integer types which can be produced by the bulk parsers. Signed types treat a `-` directly
+/// before a number (and not after another digit, so "3-5" is still two numbers) as a sign.
+pub trait ParseInt: Copy + Default {
+    const SIGNED: bool;
+
+    /// Converts a magnitude and sign, or returns `None` if the value does not fit.
+    fn from_digits(value: u64, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_parse_int {
+    ($signed:literal: $($t:ty),*) => {$(
+        impl ParseInt for $t {
+            const SIGNED: bool = $signed;
+
+            #[inline(always)]
+            fn from_digits(value: u64, negative: bool) -> Option<Self> {
+                let value = value as i128;
+                <$t>::try_from(if negative { -value } else { value }).ok()
+            }
+        }
+    )*};
+}
+
+impl_parse_int!(false: u8, u16, u32, u64, usize);
+impl_parse_int!(true: i8, i16, i32, i64, isize);
+
+/// Returns a bitmask of which of the 64 bytes starting at `offset` are ASCII digits.
+#[inline(always)]
+fn digit_mask(bytes: &[u8], offset: usize) -> u64 {
+    let chunk = match bytes.get(offset..offset + LANES) {
+        Some(chunk) => u8x64::from_slice(chunk),
+        None => {
+            let mut padded = [0u8; LANES];
+            let tail = &bytes[offset.min(bytes.len())..];
+            padded[..tail.len()].copy_from_slice(tail);
+            u8x64::from_array(padded)
+        }
+    };
+    (chunk - u8x64::splat(b'0'))
+        .simd_lt(u8x64::splat(10))
+        .to_bitmask()
+}
+
+/// Finds the next run of ASCII digits at or after `pos`, returning its bounds.
+#[inline(always)]
+fn next_digit_run(bytes: &[u8], mut pos: usize) -> Option<(usize, usize)> {
+    loop {
+        if pos >= bytes.len() {
+            return None;
+        }
+        match digit_mask(bytes, pos) {
+            0 => pos += LANES,
+            mask => {
+                pos += mask.trailing_zeros() as usize;
+                break;
+            }
+        }
+    }
+
+    let start = pos;
+    loop {
+        let run = digit_mask(bytes, pos).trailing_ones() as usize;
+        pos += run;
+        if run < LANES {
+            return Some((start, pos));
+        }
+    }
+}
+
+/// Converts exactly eight ASCII digits (most significant in the lowest byte) using SWAR.
+#[inline(always)]
+fn swar_parse_8(chunk: u64) -> u64 {
+    let chunk = chunk.wrapping_sub(0x3030_3030_3030_3030);
+    let chunk = (chunk.wrapping_mul(10) + (chunk >> 8)) & 0x00ff_00ff_00ff_00ff;
+    let chunk = (chunk.wrapping_mul(100) + (chunk >> 16)) & 0x0000_ffff_0000_ffff;
+    (chunk.wrapping_mul(10000) + (chunk >> 32)) & 0xffff_ffff
+}
+
+/// Converts a run of ASCII digits, eight at a time, or returns `None` if it overflows `u64`.
+#[inline(always)]
+fn swar_parse(digits: &[u8]) -> Option<u64> {
+    let zeros = digits.iter().take_while(|&&d| d == b'0').count();
+    let digits = &digits[zeros..];
+    if digits.len() > 20 {
+        return None;
+    }
+
+    let head = digits.len() % 8;
+    let mut value = 0u64;
+    if head != 0 {
+        let mut buf = [b'0'; 8];
+        buf[8 - head..].copy_from_slice(&digits[..head]);
+        value = swar_parse_8(u64::from_le_bytes(buf));
+    }
+    for chunk in digits[head..].chunks_exact(8) {
+        let chunk = u64::from_le_bytes(chunk.try_into().unwrap());
+        value = value
+            .checked_mul(100_000_000)?
+            .checked_add(swar_parse_8(chunk))?;
+    }
+    Some(value)
+}
+
+/// Iterator over all integers within a byte slice, skipping any non-digit delimiters.
+pub struct IntIter<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: ParseInt> Iterator for IntIter<'_, T> {
+    type Item = T;
+
+    /// Panics if a number does not fit in `T`.
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, end) = next_digit_run(self.bytes, self.pos)?;
+        self.pos = end;
+        let negative = T::SIGNED
+            && start > 0
+            && self.bytes[start - 1] == b'-'
+            && (start < 2 || !self.bytes[start - 2].is_ascii_digit());
+        let digits = &self.bytes[start..end];
+        let value = swar_parse(digits).and_then(|value| T::from_digits(value, negative));
+        Some(value.unwrap_or_else(|| {
+            panic!(
+                "Integer {}{} does not fit in {}",
+                if negative { "-" } else { "" },
+                String::from_utf8_lossy(digits),
+                std::any::type_name::<T>()
+            )
+        }))
+    }
+}
+
+/// Returns an iterator over all integers in `bytes`.
+#[inline(always)]
+pub fn ints<T: ParseInt>(bytes: &[u8]) -> IntIter<'_, T> {
+    IntIter {
+        bytes,
+        pos: 0,
+        _marker: std::marker::PhantomData,
+    }
+}
+
+/// Appends all integers in `bytes` to `out`, returning how many were found.
+#[inline(always)]
+pub fn extract_ints<T: ParseInt>(bytes: &[u8], out: &mut Vec<T>) -> usize {
+    let before = out.len();
+    out.extend(ints::<T>(bytes));
+    out.len() - before
+}
+
+/// Parses the first `N` integers of a line, panicking if there are fewer.
+#[inline(always)]
+pub fn parse_line_ints<const N: usize, T: ParseInt>(line: &[u8]) -> [T; N] {
+    let mut iter = ints::<T>(line);
+    std::array::from_fn(|_| iter.next().expect("Line has too few integers"))
+}
+
+/// Returns an iterator parsing `N` integers from each non-empty line of `bytes`.
+#[inline(always)]
+pub fn ints_per_line<const N: usize, T: ParseInt>(bytes: &[u8]) -> impl Iterator<Item = [T; N]> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        while offset < bytes.len() {
+            let end = memchr(b'\n', &bytes[offset..]).map_or(bytes.len(), |rel| offset + rel);
+            let line = &bytes[offset..end];
+            offset = end + 1;
+            if !line.is_empty() {
+                return Some(parse_line_ints(line));
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_ints() {
+        let input = b"162,817,812\n57,-618,57\n3-5 x=-12, 12345678901234567 0";
+        let mut out: Vec<u64> = Vec::new();
+        assert_eq!(extract_ints(input, &mut out), 11);
+        assert_eq!(
+            out,
+            [162, 817, 812, 57, 618, 57, 3, 5, 12, 12345678901234567, 0]
+        );
+
+        let signed: Vec<i64> = ints(input).collect();
+        assert_eq!(
+            signed,
+            [162, 817, 812, 57, -618, 57, 3, 5, -12, 12345678901234567, 0]
+        );
+
+        let long = "0".repeat(150) + "7 42";
+        assert_eq!(ints::<u64>(long.as_bytes()).collect::<Vec<_>>(), [7, 42]);
+
+        assert_eq!(ints::<u64>(b"18446744073709551615").next(), Some(u64::MAX));
+        assert_eq!(ints::<i8>(b"-128 127").collect::<Vec<_>>(), [-128, 127]);
+        assert_eq!(ints::<i64>(b"-9223372036854775808").next(), Some(i64::MIN));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(swar_parse(b"18446744073709551616"), None);
+        assert_eq!(swar_parse(b"99999999999999999999"), None);
+        assert_eq!(swar_parse(&[b'9'; 150]), None);
+        assert_eq!(i64::from_digits(9223372036854775808, false), None);
+        assert_eq!(i8::from_digits(129, true), None);
+        assert_eq!(u8::from_digits(256, false), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow_panics() {
+        ints::<u32>(b"1,4294967296").for_each(drop);
+    }
+
+    #[test]
+    fn test_ints_per_line() {
+        let lines: Vec<[i32; 2]> = ints_per_line(b"7,1\n11,-1\n\n2,3").collect();
+        assert_eq!(lines, [[7, 1], [11, -1], [2, 3]]);
+        assert_eq!(parse_line_ints::<3, usize>(b"12x5: 1 0"), [12, 5, 1]);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::compress::{CompressedAxis, SummedAreaTable};
+use crate::geom::{Point2D, Rect2D};
+
+/// Where a point lies relative to a polygon.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A simple lattice polygon given by its vertices in order (either winding).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Point2D>,
+}
+
+impl FromIterator<Point2D> for Polygon {
+    fn from_iter<I: IntoIterator<Item = Point2D>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+impl Polygon {
+    #[inline(always)]
+    pub fn new(vertices: Vec<Point2D>) -> Self {
+        debug_assert!(vertices.len() >= 3, "Polygon needs at least 3 vertices");
+        Self { vertices }
+    }
+
+    /// Parses a polygon from lines of the form "x,y".
+    pub fn from_csv_lines(input: &str) -> Self {
+        input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(Point2D::from_csv_str)
+            .collect()
+    }
+
+    #[inline(always)]
+    pub fn vertices(&self) -> &[Point2D] {
+        &self.vertices
+    }
+
+    /// Returns an iterator over the edges, including the closing edge.
+    #[inline(always)]
+    pub fn edges(&self) -> impl Iterator<Item = (Point2D, Point2D)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Returns whether every edge is horizontal or vertical.
+    pub fn is_rectilinear(&self) -> bool {
+        self.edges().all(|(a, b)| a.x == b.x || a.y == b.y)
+    }
+
+    /// Returns twice the enclosed area using the shoelace formula.
+    pub fn twice_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<i64>()
+            .abs()
+    }
+
+    /// Returns the enclosed area, which is always exact for rectilinear polygons.
+    #[inline(always)]
+    pub fn area(&self) -> i64 {
+        self.twice_area() / 2
+    }
+
+    /// Returns the number of lattice points on the boundary.
+    pub fn boundary_points(&self) -> i64 {
+        fn gcd(a: i64, b: i64) -> i64 {
+            if b == 0 { a } else { gcd(b, a % b) }
+        }
+        self.edges()
+            .map(|(a, b)| gcd((b.x - a.x).abs(), (b.y - a.y).abs()))
+            .sum()
+    }
+
+    /// Returns the number of lattice points strictly inside, using Pick's theorem.
+    #[inline(always)]
+    pub fn interior_points(&self) -> i64 {
+        (self.twice_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Returns the number of lattice points inside or on the boundary.
+    #[inline(always)]
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /// Locates a point given in doubled coordinates, so half-integer points can be tested exactly.
+    fn locate_doubled(&self, px: i64, py: i64) -> Location {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (ax, ay, bx, by) = (2 * a.x, 2 * a.y, 2 * b.x, 2 * b.y);
+
+            let cross =
+                (bx - ax) as i128 * (py - ay) as i128 - (by - ay) as i128 * (px - ax) as i128;
+            if cross == 0
+                && (ax.min(bx)..=ax.max(bx)).contains(&px)
+                && (ay.min(by)..=ay.max(by)).contains(&py)
+            {
+                return Location::Boundary;
+            }
+
+            // Count crossings of a ray cast towards +x.
+            if (ay > py) != (by > py) {
+                let crosses = if by > ay { cross > 0 } else { cross < 0 };
+                inside ^= crosses;
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    #[inline(always)]
+    pub fn locate(&self, p: &Point2D) -> Location {
+        self.locate_doubled(2 * p.x, 2 * p.y)
+    }
+
+    /// Returns whether the point lies inside or on the boundary.
+    #[inline(always)]
+    pub fn contains_point(&self, p: &Point2D) -> bool {
+        self.locate(p) != Location::Outside
+    }
+
+    /// Returns whether the closed rectangle lies entirely within the closed polygon. The polygon
+    /// must be rectilinear.
+    pub fn contains_rect(&self, rect: &Rect2D) -> bool {
+        debug_assert!(self.is_rectilinear());
+
+        if rect.min_x == rect.max_x || rect.min_y == rect.max_y {
+            return self.contains_segment(rect);
+        }
+
+        // If no edge passes through the open interior then it is wholly inside or outside, so
+        // checking the centre decides it.
+        self.edges()
+            .all(|(a, b)| !Rect2D::from_corners(&a, &b).intersects(rect))
+            && self.locate_doubled(rect.min_x + rect.max_x, rect.min_y + rect.max_y)
+                == Location::Inside
+    }
+
+    /// Builds an index for constant time lattice rectangle queries. The polygon must be
+    /// rectilinear.
+    #[inline(always)]
+    pub fn lattice_index(&self) -> LatticeIndex {
+        LatticeIndex::new(self)
+    }
+
+    /// Handles `contains_rect` for rectangles which are a single point or line.
+    fn contains_segment(&self, rect: &Rect2D) -> bool {
+        let horizontal = rect.min_y == rect.max_y;
+        let (lo, hi) = match horizontal {
+            true => (rect.min_x, rect.max_x),
+            false => (rect.min_y, rect.max_y),
+        };
+
+        // The segment can only leave the polygon where it meets an edge, so test every such
+        // point and the midpoints between them.
+        let mut stops = vec![lo, hi];
+        for (a, b) in self.edges() {
+            let (a, b) = match horizontal {
+                true => (a.x, b.x),
+                false => (a.y, b.y),
+            };
+            stops.extend([a, b].into_iter().filter(|t| (lo..=hi).contains(t)));
+        }
+        stops.sort_unstable();
+        stops.dedup();
+
+        let at = |t2: i64| match horizontal {
+            true => self.locate_doubled(t2, 2 * rect.min_y),
+            false => self.locate_doubled(2 * rect.min_x, t2),
+        };
+        stops.iter().all(|&t| at(2 * t) != Location::Outside)
+            && stops
+                .windows(2)
+                .all(|w| at(w[0] + w[1]) != Location::Outside)
+    }
+}
+
+/// Answers "is every lattice point of this rectangle inside or on the polygon?" in O(1).
+///
+/// Each vertex contributes `x` and `x + 1` (and likewise for `y`) to the compressed axes, so every
+/// compressed block is either a single row or column holding an edge, or free of edges entirely.
+/// All lattice points within a block therefore share the same status.
+#[derive(Debug, Clone)]
+pub struct LatticeIndex {
+    xs: CompressedAxis,
+    ys: CompressedAxis,
+    outside: SummedAreaTable,
+}
+
+impl LatticeIndex {
+    pub fn new(polygon: &Polygon) -> Self {
+        debug_assert!(polygon.is_rectilinear());
+
+        let xs: CompressedAxis = polygon
+            .vertices
+            .iter()
+            .flat_map(|p| [p.x, p.x + 1])
+            .collect();
+        let ys: CompressedAxis = polygon
+            .vertices
+            .iter()
+            .flat_map(|p| [p.y, p.y + 1])
+            .collect();
+        let (width, height) = (xs.num_blocks(), ys.num_blocks());
+
+        let mut boundary = vec![false; width * height];
+        let mut crossings = vec![false; (width + 1) * height];
+        for (a, b) in polygon.edges() {
+            let (x0, x1) = (
+                xs.index_of(a.x.min(b.x)).unwrap(),
+                xs.index_of(a.x.max(b.x)).unwrap(),
+            );
+            let (y0, y1) = (
+                ys.index_of(a.y.min(b.y)).unwrap(),
+                ys.index_of(a.y.max(b.y)).unwrap(),
+            );
+            for y in y0..=y1 {
+                boundary[y * width + x0..=y * width + x1].fill(true);
+            }
+
+            // A ray cast towards +x from blocks left of a vertical edge crosses it, counting the
+            // lower end but not the upper end.
+            if a.x == b.x {
+                for y in y0..y1 {
+                    crossings[y * (width + 1)] ^= true;
+                    crossings[y * (width + 1) + x0] ^= true;
+                }
+            }
+        }
+
+        let mut inside = vec![false; width * height];
+        for y in 0..height {
+            let mut parity = false;
+            for x in 0..width {
+                parity ^= crossings[y * (width + 1) + x];
+                inside[y * width + x] = parity || boundary[y * width + x];
+            }
+        }
+
+        let outside = SummedAreaTable::from_fn(width, height, |x, y| !inside[y * width + x] as i64);
+        Self { xs, ys, outside }
+    }
+
+    /// Returns whether every lattice point of the rectangle is inside or on the polygon.
+    #[inline(always)]
+    pub fn contains_rect(&self, rect: &Rect2D) -> bool {
+        match (
+            self.xs.block_of(rect.min_x),
+            self.xs.block_of(rect.max_x),
+            self.ys.block_of(rect.min_y),
+            self.ys.block_of(rect.max_y),
+        ) {
+            (Some(x0), Some(x1), Some(y0), Some(y1)) => self.outside.sum(x0, y0, x1, y1) == 0,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    fn u_shape() -> Polygon {
+        // A U with its opening at the top between x = 2 and x = 4.
+        Polygon::from_csv_lines(indoc! {"
+            0,0
+            6,0
+            6,6
+            4,6
+            4,2
+            2,2
+            2,6
+            0,6
+        "})
+    }
+
+    #[test]
+    fn test_area_and_lattice_counts() {
+        let polygon = u_shape();
+        assert_eq!(polygon.area(), 36 - 8);
+        assert_eq!(polygon.boundary_points(), 32);
+        assert_eq!(polygon.interior_points(), 13);
+        assert_eq!(polygon.lattice_points(), 49 - 4);
+    }
+
+    #[test]
+    fn test_contains() {
+        let polygon = u_shape();
+        assert_eq!(polygon.locate(&Point2D::new(1, 1)), Location::Inside);
+        assert_eq!(polygon.locate(&Point2D::new(4, 3)), Location::Boundary);
+        assert_eq!(polygon.locate(&Point2D::new(3, 4)), Location::Outside);
+
+        let rect =
+            |x1, y1, x2, y2| Rect2D::from_corners(&Point2D::new(x1, y1), &Point2D::new(x2, y2));
+        assert!(polygon.contains_rect(&rect(0, 0, 6, 2)));
+        assert!(polygon.contains_rect(&rect(0, 6, 2, 0)));
+        assert!(!polygon.contains_rect(&rect(0, 0, 6, 3)));
+        assert!(!polygon.contains_rect(&rect(1, 1, 5, 5)));
+
+        // Segments along the top of the U cross the opening.
+        assert!(polygon.contains_rect(&rect(0, 2, 6, 2)));
+        assert!(!polygon.contains_rect(&rect(0, 6, 6, 6)));
+        assert!(polygon.contains_rect(&rect(4, 6, 6, 6)));
+    }
+
+    #[test]
+    fn test_lattice_index() {
+        let polygon = u_shape();
+        let index = polygon.lattice_index();
+        let rect =
+            |x1, y1, x2, y2| Rect2D::from_corners(&Point2D::new(x1, y1), &Point2D::new(x2, y2));
+        for (x1, y1) in (0..=7).flat_map(|x| (0..=7).map(move |y| (x, y))) {
+            for (x2, y2) in (x1..=7).flat_map(|x| (y1..=7).map(move |y| (x, y))) {
+                let r = rect(x1, y1, x2, y2);
+                let expected = (x1..=x2)
+                    .flat_map(|x| (y1..=y2).map(move |y| Point2D::new(x, y)))
+                    .all(|p| polygon.contains_point(&p));
+                assert_eq!(index.contains_rect(&r), expected, "{r:?}");
+            }
+        }
+
+        // Adjacent edges leave no lattice points outside between them, unlike the continuous
+        // polygon.
+        let notched = Polygon::from_csv_lines("0,0\n5,0\n5,4\n3,4\n3,1\n2,1\n2,4\n0,4\n");
+        assert!(notched.lattice_index().contains_rect(&rect(0, 0, 5, 4)));
+        assert!(!notched.contains_rect(&rect(0, 0, 5, 4)));
+    }
+}
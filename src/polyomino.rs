@@ -0,0 +1,647 @@
+use crate::budget::Budget;
+use crate::exact_cover::ExactCover;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::{env, fs};
+
+/// Environment variable naming a directory to write packed layouts to as PPM images.
+pub const PPM_OUTPUT_VAR: &str = "AOC_PPM";
+
+/// Environment variable setting the packing [`Budget`]: a node count such as `1000000`, or a time
+/// per region such as `250ms` or `2s`.
+pub const BUDGET_VAR: &str = "AOC_PACK_BUDGET";
+
+/// The most cells a board may have for [`pack_with_budget`] to search it, which keeps its exact
+/// cover to a few million entries.
+pub const MAX_SEARCH_CELLS: usize = 128 * 128;
+
+/// A polyomino as a set of `(x, y)` cells, normalised so its bounding box starts at the origin
+/// and the cells are in row-major order.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Polyomino {
+    cells: Vec<(usize, usize)>,
+}
+
+impl FromStr for Polyomino {
+    type Err = ();
+
+    /// Parses rows of `#` (filled) and `.` (empty) cells.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = s.lines().enumerate().flat_map(|(y, line)| {
+            line.bytes()
+                .enumerate()
+                .filter(|&(_, b)| b == b'#')
+                .map(move |(x, _)| (x as isize, y as isize))
+        });
+        Ok(Self::from_cells(cells))
+    }
+}
+
+impl Polyomino {
+    /// Builds a polyomino from cells at any offset.
+    pub fn from_cells(cells: impl IntoIterator<Item = (isize, isize)>) -> Self {
+        let cells: Vec<(isize, isize)> = cells.into_iter().collect();
+        let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+        let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+        let mut cells: Vec<(usize, usize)> = cells
+            .into_iter()
+            .map(|(x, y)| ((x - min_x) as usize, (y - min_y) as usize))
+            .collect();
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells.dedup();
+        Self { cells }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    #[inline(always)]
+    pub fn width(&self) -> usize {
+        self.cells.iter().map(|c| c.0 + 1).max().unwrap_or(0)
+    }
+
+    #[inline(always)]
+    pub fn height(&self) -> usize {
+        self.cells.last().map_or(0, |c| c.1 + 1)
+    }
+
+    /// Returns the polyomino rotated a quarter turn clockwise.
+    pub fn rotate(&self) -> Self {
+        let height = self.height() as isize;
+        Self::from_cells(
+            self.cells
+                .iter()
+                .map(|&(x, y)| (height - 1 - y as isize, x as isize)),
+        )
+    }
+
+    /// Returns the polyomino mirrored left to right.
+    pub fn flip(&self) -> Self {
+        let width = self.width() as isize;
+        Self::from_cells(
+            self.cells
+                .iter()
+                .map(|&(x, y)| (width - 1 - x as isize, y as isize)),
+        )
+    }
+
+    /// Returns every distinct rotation and reflection, in sorted order.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        let mut shape = self.clone();
+        for _ in 0..4 {
+            orientations.push(shape.flip());
+            shape = shape.rotate();
+            orientations.push(shape.clone());
+        }
+        orientations.sort_unstable();
+        orientations.dedup();
+        orientations
+    }
+}
+
+/// A piece placed on the board: the index of its shape and the board cells it covers.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Placement {
+    pub shape: usize,
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Places `counts[i]` copies of each `shapes[i]`, freely rotated and flipped, without overlap on
+/// a `width` by `height` board, searching as long as it takes.
+///
+/// Regions which are too small or roomy enough to give every piece its own bounding box are
+/// settled first. Anything else is searched exactly as an [`ExactCover`] of the board's cells by
+/// the pieces and as many empty cells as there is area to spare.
+#[inline(always)]
+pub fn pack(width: usize, height: usize, shapes: &[Polyomino], counts: &[usize]) -> Packing {
+    let orientations: Vec<Vec<Polyomino>> = shapes.iter().map(Polyomino::orientations).collect();
+    pack_with_budget(width, height, &orientations, counts, Budget::default())
+}
+
+/// How a region was shown not to fit.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Disproof {
+    /// The pieces have more cells than the region.
+    Area,
+    /// No choice of checkerboard colouring for the pieces balances against the region's colours.
+    Colouring,
+    /// Searching every arrangement found none.
+    Exhausted,
+}
+
+/// The result of [`pack_with_budget`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Packing {
+    Fits(Vec<Placement>),
+    DoesNotFit(Disproof),
+    /// The budget ran out before the search could decide.
+    Undecided,
+}
+
+/// As [`pack`], for shapes given by their sets of allowed orientations, giving up once `budget`
+/// is spent. Cheap proofs that the pieces don't fit are tried before searching, and boards of
+/// more than [`MAX_SEARCH_CELLS`] are left undecided if those can't settle them.
+pub fn pack_with_budget(
+    width: usize,
+    height: usize,
+    orientations: &[Vec<Polyomino>],
+    counts: &[usize],
+    budget: Budget,
+) -> Packing {
+    assert_eq!(
+        orientations.len(),
+        counts.len(),
+        "Every shape needs a count"
+    );
+    let shapes: Vec<Polyomino> = orientations
+        .iter()
+        .map(|o| o.first().cloned().unwrap_or_default())
+        .collect();
+    // Pieces without any cells take no room, so only the rest need placing.
+    let counts: Vec<usize> = shapes
+        .iter()
+        .zip(counts)
+        .map(|(s, &c)| if s.is_empty() { 0 } else { c })
+        .collect();
+    let counts = &counts[..];
+    let needed: usize = shapes.iter().zip(counts).map(|(s, &c)| s.len() * c).sum();
+    if needed > width * height {
+        return Packing::DoesNotFit(Disproof::Area);
+    }
+
+    if let Some(placements) = pack_in_boxes(width, height, &shapes, counts)
+        .or_else(|| pack_in_boxes(width, height, &turned(orientations), counts))
+    {
+        return Packing::Fits(placements);
+    }
+    if !colouring_allows(width, height, &shapes, counts) {
+        return Packing::DoesNotFit(Disproof::Colouring);
+    }
+
+    if width * height > MAX_SEARCH_CELLS {
+        return Packing::Undecided;
+    }
+    let slack = width * height - needed;
+    let (problem, mut rows) = packing_problem(width, height, orientations, counts, slack);
+    match problem.search().with_budget(budget).next_solution() {
+        Ok(Some(solution)) => Packing::Fits(
+            solution
+                .into_iter()
+                .filter_map(|row| rows[row].take())
+                .collect(),
+        ),
+        Ok(None) => Packing::DoesNotFit(Disproof::Exhausted),
+        Err(_) => Packing::Undecided,
+    }
+}
+
+/// Builds the exact cover for packing a board. Each cell is a primary column, and so is each
+/// shape in use with its count as the multiplicity, with one more needing `slack` empty cells.
+/// Returns the placement each row stands for, or `None` for rows leaving a cell empty.
+fn packing_problem(
+    width: usize,
+    height: usize,
+    orientations: &[Vec<Polyomino>],
+    counts: &[usize],
+    slack: usize,
+) -> (ExactCover, Vec<Option<Placement>>) {
+    let cells = width * height;
+    let used: Vec<usize> = (0..counts.len()).filter(|&s| counts[s] > 0).collect();
+    let empty = cells + used.len();
+    let mut problem = ExactCover::new(empty + usize::from(slack > 0), 0);
+    let mut rows = Vec::new();
+
+    for (i, &shape) in used.iter().enumerate() {
+        problem.set_multiplicity(cells + i, counts[shape]);
+        for o in &orientations[shape] {
+            for top in 0..(height + 1).saturating_sub(o.height()) {
+                for left in 0..(width + 1).saturating_sub(o.width()) {
+                    let placed: Vec<(usize, usize)> = o
+                        .cells()
+                        .iter()
+                        .map(|&(x, y)| (left + x, top + y))
+                        .collect();
+                    problem.add_row(
+                        placed
+                            .iter()
+                            .map(|&(x, y)| y * width + x)
+                            .chain([cells + i]),
+                    );
+                    rows.push(Some(Placement {
+                        shape,
+                        cells: placed,
+                    }));
+                }
+            }
+        }
+    }
+
+    if slack > 0 {
+        problem.set_multiplicity(empty, slack);
+        for cell in 0..cells {
+            problem.add_row([cell, empty]);
+            rows.push(None);
+        }
+    }
+    (problem, rows)
+}
+
+/// Checks the pieces against a checkerboard colouring of the board. A piece covering `b` black
+/// and `w` white cells covers the same counts or the two swapped wherever it is placed, so some
+/// choice of swaps must keep both colours within what the board has.
+fn colouring_allows(width: usize, height: usize, shapes: &[Polyomino], counts: &[usize]) -> bool {
+    let board_black = (width * height).div_ceil(2);
+    let board_white = width * height / 2;
+
+    // Track every achievable black cell count, starting from each piece's lighter colouring.
+    let mut base = 0;
+    let mut total = 0;
+    let mut reachable = vec![true];
+    for (shape, &count) in shapes.iter().zip(counts) {
+        let black = shape
+            .cells()
+            .iter()
+            .filter(|(x, y)| (x + y) % 2 == 0)
+            .count();
+        let (light, heavy) = (
+            black.min(shape.len() - black),
+            black.max(shape.len() - black),
+        );
+        base += light * count;
+        total += shape.len() * count;
+        let swing = heavy - light;
+        for _ in 0..count {
+            let mut next = vec![false; reachable.len() + swing];
+            for (i, _) in reachable.iter().enumerate().filter(|(_, r)| **r) {
+                next[i] = true;
+                next[i + swing] = true;
+            }
+            reachable = next;
+        }
+    }
+
+    reachable
+        .iter()
+        .enumerate()
+        .any(|(i, &r)| r && base + i <= board_black && total - (base + i) <= board_white)
+}
+
+/// Picks an allowed orientation of each shape with its bounding box on its side, keeping the
+/// first for shapes which have none.
+#[inline(always)]
+fn turned(orientations: &[Vec<Polyomino>]) -> Vec<Polyomino> {
+    orientations
+        .iter()
+        .map(|o| {
+            let first = o.first().cloned().unwrap_or_default();
+            o.iter()
+                .find(|t| (t.width(), t.height()) == (first.height(), first.width()))
+                .cloned()
+                .unwrap_or(first)
+        })
+        .collect()
+}
+
+/// Places every piece in its own slot of a grid sized by the largest bounding box, if there are
+/// enough slots.
+fn pack_in_boxes(
+    width: usize,
+    height: usize,
+    shapes: &[Polyomino],
+    counts: &[usize],
+) -> Option<Vec<Placement>> {
+    let used = || shapes.iter().zip(counts).filter(|(_, c)| **c > 0);
+    let box_width = used().map(|(s, _)| s.width()).max().unwrap_or(1);
+    let box_height = used().map(|(s, _)| s.height()).max().unwrap_or(1);
+    let columns = width / box_width;
+    if columns * (height / box_height) < counts.iter().sum() {
+        return None;
+    }
+
+    let pieces = used()
+        .enumerate()
+        .flat_map(|(i, (s, &c))| (0..c).map(move |_| (i, s)));
+    let shape_index: Vec<usize> = (0..shapes.len()).filter(|&i| counts[i] > 0).collect();
+    let placements = pieces
+        .enumerate()
+        .map(|(slot, (i, shape))| {
+            let (x0, y0) = (slot % columns * box_width, slot / columns * box_height);
+            Placement {
+                shape: shape_index[i],
+                cells: shape
+                    .cells()
+                    .iter()
+                    .map(|&(x, y)| (x0 + x, y0 + y))
+                    .collect(),
+            }
+        })
+        .collect();
+    Some(placements)
+}
+
+/// One colour per shape index, cycled if there are more shapes.
+const PALETTE: [(u8, u8, u8); 8] = [
+    (230, 97, 92),
+    (242, 177, 52),
+    (240, 226, 107),
+    (125, 196, 120),
+    (86, 180, 233),
+    (148, 122, 209),
+    (232, 139, 196),
+    (170, 140, 100),
+];
+
+/// Which piece covers each cell of a packed board, for display.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Layout {
+    width: usize,
+    height: usize,
+    /// The covering piece of each cell in row-major order.
+    pieces: Vec<Option<usize>>,
+    /// The shape index of each piece.
+    shapes: Vec<usize>,
+}
+
+impl Layout {
+    pub fn new(width: usize, height: usize, placements: &[Placement]) -> Self {
+        let mut pieces = vec![None; width * height];
+        for (piece, placement) in placements.iter().enumerate() {
+            for &(x, y) in &placement.cells {
+                debug_assert!(pieces[y * width + x].is_none(), "Pieces overlap");
+                pieces[y * width + x] = Some(piece);
+            }
+        }
+        Self {
+            width,
+            height,
+            pieces,
+            shapes: placements.iter().map(|p| p.shape).collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn piece(&self, x: usize, y: usize) -> Option<usize> {
+        self.pieces[y * self.width + x]
+    }
+
+    /// Labels pieces `A` to `Z` then `a` to `z`, repeating after that.
+    #[inline(always)]
+    fn letter(piece: usize) -> char {
+        let i = (piece % 52) as u8;
+        match i {
+            0..26 => (b'A' + i) as char,
+            _ => (b'a' + i - 26) as char,
+        }
+    }
+
+    #[inline(always)]
+    fn colour(&self, piece: usize) -> (u8, u8, u8) {
+        PALETTE[self.shapes[piece] % PALETTE.len()]
+    }
+
+    /// Renders the letter grid with each piece on a background coloured by its shape, using ANSI
+    /// 24-bit colour escapes.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                match self.piece(x, y) {
+                    Some(piece) => {
+                        let (r, g, b) = self.colour(piece);
+                        let letter = Self::letter(piece);
+                        write!(out, "\x1b[30;48;2;{r};{g};{b}m{letter}").unwrap();
+                    }
+                    None => out.push_str("\x1b[0m."),
+                }
+            }
+            out.push_str("\x1b[0m\n");
+        }
+        out
+    }
+
+    /// Writes a binary PPM image with `scale` pixels to a cell, coloured by shape, with a dark
+    /// outline wherever two pieces meet.
+    pub fn write_ppm(&self, w: &mut impl Write, scale: usize) -> io::Result<()> {
+        let (width, height) = (self.width * scale, self.height * scale);
+        writeln!(w, "P6\n{width} {height}\n255")?;
+
+        let mut row = Vec::with_capacity(width * 3);
+        for py in 0..height {
+            row.clear();
+            let (y, sy) = (py / scale, py % scale);
+            for px in 0..width {
+                let (x, sx) = (px / scale, px % scale);
+                let piece = self.piece(x, y);
+                let edge = (sx == 0 && (x == 0 || self.piece(x - 1, y) != piece))
+                    || (sy == 0 && (y == 0 || self.piece(x, y - 1) != piece))
+                    || (sx == scale - 1 && (x + 1 == self.width || self.piece(x + 1, y) != piece))
+                    || (sy == scale - 1 && (y + 1 == self.height || self.piece(x, y + 1) != piece));
+                let (r, g, b) = match piece {
+                    _ if edge => (32, 32, 32),
+                    Some(piece) => self.colour(piece),
+                    None => (255, 255, 255),
+                };
+                row.extend([r, g, b]);
+            }
+            w.write_all(&row)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the layout as `name.ppm` in the directory named by [`PPM_OUTPUT_VAR`], if it is set.
+    pub fn write_ppm_if_requested(&self, name: &str) {
+        if let Some(dir) = env::var_os(PPM_OUTPUT_VAR) {
+            let path = Path::new(&dir).join(format!("{name}.ppm"));
+            let mut file =
+                io::BufWriter::new(fs::File::create(path).expect("Could not create PPM"));
+            self.write_ppm(&mut file, 16)
+                .expect("Could not write PPM output");
+        }
+    }
+}
+
+impl Display for Layout {
+    /// Writes one line per row, with each piece's cells as its letter and empty cells as `.`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                f.write_char(self.piece(x, y).map_or('.', Self::letter))?;
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(s: &str) -> Polyomino {
+        s.replace(' ', "\n").parse().unwrap()
+    }
+
+    #[test]
+    fn test_orientations() {
+        let l = shape("#.. ###");
+        assert_eq!((l.width(), l.height(), l.len()), (3, 2, 4));
+        assert_eq!(l.rotate(), shape("## #. #."));
+        assert_eq!(l.flip(), shape("..# ###"));
+        assert_eq!(l.orientations().len(), 8);
+        assert_eq!(shape("## ##").orientations().len(), 1);
+        assert_eq!(shape("### .#.").orientations().len(), 4);
+    }
+
+    #[test]
+    fn test_pack() {
+        let shapes = [shape("#.. ###"), shape("##")];
+        let fits = |packing| match packing {
+            Packing::Fits(placements) => placements,
+            packing => panic!("Expected the pieces to fit, got {packing:?}"),
+        };
+
+        // Too little area, and a roomy region settled by boxes alone.
+        assert_eq!(
+            pack(3, 3, &shapes, &[2, 1]),
+            Packing::DoesNotFit(Disproof::Area)
+        );
+        assert_eq!(fits(pack(6, 4, &shapes, &[4, 0])).len(), 4);
+
+        // Two L pieces and a domino tile a 3x4 board exactly, which needs the search.
+        let placements = fits(pack(3, 4, &shapes, &[2, 2]));
+        let mut covered: Vec<_> = placements.iter().flat_map(|p| p.cells.clone()).collect();
+        covered.sort_unstable();
+        covered.dedup();
+        assert_eq!(covered.len(), 12);
+
+        // Area alone allows five L pieces in a 4x5 board, but they can't be fitted.
+        assert_eq!(
+            pack(4, 5, &shapes, &[5, 0]),
+            Packing::DoesNotFit(Disproof::Exhausted)
+        );
+
+        // Pieces without cells are placed without taking any room, even when nothing else is.
+        let empty = vec![shape(".. ..")];
+        assert_eq!(pack(1, 1, &empty, &[3]), Packing::Fits(Vec::new()));
+        let Packing::Fits(placements) = pack(2, 1, &[shape("##"), empty[0].clone()], &[1, 2])
+        else {
+            panic!("A domino fits a 2x1 board");
+        };
+        assert_eq!(placements.len(), 1);
+    }
+
+    #[test]
+    fn test_pack_with_budget() {
+        let all = |shapes: &[Polyomino]| -> Vec<Vec<Polyomino>> {
+            shapes.iter().map(Polyomino::orientations).collect()
+        };
+        let l = all(&[shape("#.. ###")]);
+        let unlimited = Budget::default();
+
+        assert_eq!(
+            pack_with_budget(2, 2, &l, &[2], unlimited),
+            Packing::DoesNotFit(Disproof::Area)
+        );
+        assert_eq!(
+            pack_with_budget(4, 5, &l, &[5], unlimited),
+            Packing::DoesNotFit(Disproof::Exhausted)
+        );
+        assert_eq!(
+            pack_with_budget(
+                4,
+                5,
+                &l,
+                &[5],
+                Budget {
+                    nodes: Some(10),
+                    time: None
+                }
+            ),
+            Packing::Undecided
+        );
+
+        // Each T piece covers three cells of one colour, so five can't balance a 4x5 board.
+        let t = all(&[shape("### .#.")]);
+        assert_eq!(
+            pack_with_budget(
+                4,
+                5,
+                &t,
+                &[5],
+                Budget {
+                    nodes: Some(0),
+                    time: None
+                }
+            ),
+            Packing::DoesNotFit(Disproof::Colouring)
+        );
+        assert!(matches!(
+            pack_with_budget(4, 4, &t, &[4], unlimited),
+            Packing::Fits(_)
+        ));
+    }
+
+    #[test]
+    fn test_pack_fixed_orientations() {
+        // Each piece must land as given, whichever way round the board is.
+        let fixed = [vec![shape("#.. ###")], vec![shape("### ..#")]];
+        let Packing::Fits(placements) = pack_with_budget(4, 2, &fixed, &[1, 1], Budget::default())
+        else {
+            panic!("Both pieces fit a 4x2 board as given");
+        };
+        for p in &placements {
+            let cells = p.cells.iter().map(|&(x, y)| (x as isize, y as isize));
+            assert_eq!(Polyomino::from_cells(cells), fixed[p.shape][0]);
+        }
+
+        // Boxes on their side would hold two, but only if the piece could be turned.
+        assert_eq!(
+            pack_with_budget(2, 6, &fixed[..1], &[2], Budget::default()),
+            Packing::DoesNotFit(Disproof::Exhausted)
+        );
+
+        // Beyond what the search takes on, only the cheap checks can answer.
+        let domino = [vec![shape("##"), shape("# #")]];
+        assert_eq!(
+            pack_with_budget(129, 129, &domino, &[8320], Budget::default()),
+            Packing::Undecided
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let placements = [
+            Placement {
+                shape: 1,
+                cells: vec![(0, 0), (1, 0)],
+            },
+            Placement {
+                shape: 0,
+                cells: vec![(2, 0), (0, 1), (1, 1), (2, 1)],
+            },
+        ];
+        let layout = Layout::new(4, 2, &placements);
+        assert_eq!(layout.to_string(), "AAB.\nBBB.\n");
+        assert!(layout.to_ansi().starts_with("\x1b[30;48;2;242;177;52mA"));
+
+        let mut ppm = Vec::new();
+        layout.write_ppm(&mut ppm, 3).unwrap();
+        assert!(ppm.starts_with(b"P6\n12 6\n255\n"));
+        assert_eq!(ppm.len(), b"P6\n12 6\n255\n".len() + 12 * 6 * 3);
+    }
+}
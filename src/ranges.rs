@@ -0,0 +1,424 @@
+use crate::parse_range;
+use atoi::FromRadix10SignedChecked;
+
+/// Integer types which can be stored in a [`RangeSet`].
+pub trait RangeInt: Copy + Ord {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// Returns the next value, saturating at `MAX`.
+    fn succ(self) -> Self;
+
+    /// Returns the previous value, saturating at `MIN`.
+    fn pred(self) -> Self;
+
+    /// Returns the number of values in the inclusive range `lo..=hi`.
+    fn span_len(lo: Self, hi: Self) -> u128;
+}
+
+macro_rules! impl_range_int {
+    ($($t:ty),*) => {$(
+        impl RangeInt for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            #[inline(always)]
+            fn succ(self) -> Self {
+                self.saturating_add(1)
+            }
+
+            #[inline(always)]
+            fn pred(self) -> Self {
+                self.saturating_sub(1)
+            }
+
+            #[inline(always)]
+            fn span_len(lo: Self, hi: Self) -> u128 {
+                (hi as i128 - lo as i128) as u128 + 1
+            }
+        }
+    )*};
+}
+
+impl_range_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive spans.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RangeSet<T> {
+    spans: Vec<(T, T)>,
+}
+
+impl<T: RangeInt> RangeSet<T> {
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self { spans: Vec::new() }
+    }
+
+    /// Builds a set from lines of the form "123-456", ignoring blank lines.
+    pub fn from_range_lines(input: &str) -> Self
+    where
+        T: FromRadix10SignedChecked,
+    {
+        input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(parse_range)
+            .collect()
+    }
+
+    /// Builds a set directly from already sorted and merged spans.
+    #[inline(always)]
+    fn from_merged(spans: Vec<(T, T)>) -> Self {
+        Self { spans }
+    }
+
+    /// Inserts the inclusive range `lo..=hi`, coalescing it with any overlapping or adjacent spans.
+    pub fn insert(&mut self, lo: T, hi: T) {
+        let (mut lo, mut hi) = if lo <= hi { (lo, hi) } else { (hi, lo) };
+
+        let first = self.spans.partition_point(|&(_, h)| h.succ() < lo);
+        let last = self.spans.partition_point(|&(l, _)| l <= hi.succ());
+        if first < last {
+            lo = lo.min(self.spans[first].0);
+            hi = hi.max(self.spans[last - 1].1);
+        }
+        self.spans.splice(first..last, [(lo, hi)]);
+    }
+
+    /// Returns whether `x` is covered by any span.
+    #[inline(always)]
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.spans.partition_point(|&(_, hi)| hi < x);
+        self.spans.get(i).is_some_and(|&(lo, _)| lo <= x)
+    }
+
+    /// Returns the total number of integers covered by the set.
+    #[inline(always)]
+    pub fn len(&self) -> u128 {
+        self.spans.iter().map(|&(lo, hi)| T::span_len(lo, hi)).sum()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the merged spans in ascending order.
+    #[inline(always)]
+    pub fn spans(&self) -> &[(T, T)] {
+        &self.spans
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.spans.iter().copied()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut merged = Vec::with_capacity(self.spans.len() + other.spans.len());
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) if x.0 <= y.0 => a.next(),
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            };
+            let Some((lo, hi)) = next else { break };
+            match merged.last_mut() {
+                Some((_, last_hi)) if lo <= T::succ(*last_hi) => *last_hi = (*last_hi).max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        Self::from_merged(merged)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.spans.len() && j < other.spans.len() {
+            let (a_lo, a_hi) = self.spans[i];
+            let (b_lo, b_hi) = other.spans[j];
+            let lo = a_lo.max(b_lo);
+            let hi = a_hi.min(b_hi);
+            if lo <= hi {
+                result.push((lo, hi));
+            }
+            if a_hi < b_hi {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self::from_merged(result)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(T::MIN, T::MAX))
+    }
+
+    /// Returns the integers within `lo..=hi` which are not covered by the set.
+    pub fn complement(&self, lo: T, hi: T) -> Self {
+        let mut result = Vec::new();
+        let mut next = lo;
+        let mut exhausted = lo > hi;
+        for &(s_lo, s_hi) in &self.spans {
+            if exhausted || s_lo > hi {
+                break;
+            }
+            if s_hi < next {
+                continue;
+            }
+            if s_lo > next {
+                result.push((next, s_lo.pred()));
+            }
+            if s_hi >= hi {
+                exhausted = true;
+            } else {
+                next = s_hi.succ();
+            }
+        }
+        if !exhausted {
+            result.push((next, hi));
+        }
+        Self::from_merged(result)
+    }
+}
+
+impl<T: RangeInt> FromIterator<(T, T)> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut spans: Vec<(T, T)> = iter
+            .into_iter()
+            .map(|(lo, hi)| if lo <= hi { (lo, hi) } else { (hi, lo) })
+            .collect();
+        spans.sort_unstable();
+
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(spans.len());
+        for (lo, hi) in spans {
+            match merged.last_mut() {
+                Some((_, last_hi)) if lo <= T::succ(*last_hi) => *last_hi = (*last_hi).max(hi),
+                _ => merged.push((lo, hi)),
+            }
+        }
+        Self::from_merged(merged)
+    }
+}
+
+/// A static index over a fixed collection of inclusive ranges for answering large numbers of
+/// membership queries. Merged span starts are stored in Eytzinger (BFS) order so lookups are a
+/// branch-free, cache friendly descent.
+#[derive(Debug, Clone)]
+pub struct RangeIndex<T> {
+    set: RangeSet<T>,
+    eytzinger: Vec<T>,
+    eytzinger_order: Vec<usize>,
+    prefix_len: Vec<u128>,
+    originals: Vec<(T, T, usize)>,
+    group_start: Vec<usize>,
+}
+
+impl<T: RangeInt> RangeIndex<T> {
+    pub fn new<I: IntoIterator<Item = (T, T)>>(ranges: I) -> Self {
+        let mut originals: Vec<(T, T, usize)> = ranges
+            .into_iter()
+            .map(|(lo, hi)| if lo <= hi { (lo, hi) } else { (hi, lo) })
+            .enumerate()
+            .map(|(i, (lo, hi))| (lo, hi, i))
+            .collect();
+        originals.sort_unstable();
+
+        let set: RangeSet<T> = originals.iter().map(|&(lo, hi, _)| (lo, hi)).collect();
+        let spans = set.spans();
+        let n = spans.len();
+
+        // Lay the span starts out in Eytzinger order (1-indexed, slot 0 unused).
+        let mut eytzinger = vec![T::MIN; n + 1];
+        let mut eytzinger_order = vec![0; n + 1];
+        fn fill<T: Copy>(
+            spans: &[(T, T)],
+            ey: &mut [T],
+            order: &mut [usize],
+            i: &mut usize,
+            k: usize,
+        ) {
+            if k < ey.len() {
+                fill(spans, ey, order, i, 2 * k);
+                ey[k] = spans[*i].0;
+                order[k] = *i;
+                *i += 1;
+                fill(spans, ey, order, i, 2 * k + 1);
+            }
+        }
+        fill(spans, &mut eytzinger, &mut eytzinger_order, &mut 0, 1);
+
+        let mut prefix_len = Vec::with_capacity(n + 1);
+        prefix_len.push(0);
+        for &(lo, hi) in spans {
+            prefix_len.push(prefix_len.last().unwrap() + T::span_len(lo, hi));
+        }
+
+        let mut group_start = Vec::with_capacity(n + 1);
+        let mut j = 0;
+        for &(lo, _) in spans {
+            while originals[j].0 < lo {
+                j += 1;
+            }
+            group_start.push(j);
+        }
+        group_start.push(originals.len());
+
+        Self {
+            set,
+            eytzinger,
+            eytzinger_order,
+            prefix_len,
+            originals,
+            group_start,
+        }
+    }
+
+    pub fn from_range_lines(input: &str) -> Self
+    where
+        T: FromRadix10SignedChecked,
+    {
+        Self::new(
+            input
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(parse_range),
+        )
+    }
+
+    /// Returns the merged set of ranges backing the index.
+    #[inline(always)]
+    pub fn set(&self) -> &RangeSet<T> {
+        &self.set
+    }
+
+    /// Returns the number of merged spans whose start is `<= x`.
+    #[inline(always)]
+    fn spans_starting_at_or_before(&self, x: T) -> usize {
+        let n = self.eytzinger.len() - 1;
+        let mut k = 1;
+        while k <= n {
+            k = 2 * k + (unsafe { *self.eytzinger.get_unchecked(k) } <= x) as usize;
+        }
+        k >>= k.trailing_ones() + 1;
+        if k == 0 { n } else { self.eytzinger_order[k] }
+    }
+
+    /// Returns the merged span containing `x` by index, if any.
+    #[inline(always)]
+    fn span_index(&self, x: T) -> Option<usize> {
+        let i = self.spans_starting_at_or_before(x).checked_sub(1)?;
+        (self.set.spans[i].1 >= x).then_some(i)
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, x: T) -> bool {
+        self.span_index(x).is_some()
+    }
+
+    /// Counts how many of the given IDs are covered by any range.
+    #[inline(always)]
+    pub fn count_contained<I: IntoIterator<Item = T>>(&self, ids: I) -> usize {
+        ids.into_iter().filter(|&x| self.contains(x)).count()
+    }
+
+    /// Returns the number of covered integers which are `<= x`.
+    #[inline(always)]
+    fn covered_up_to(&self, x: T) -> u128 {
+        match self.spans_starting_at_or_before(x) {
+            0 => 0,
+            i => {
+                let (lo, hi) = self.set.spans[i - 1];
+                self.prefix_len[i - 1] + T::span_len(lo, hi.min(x))
+            }
+        }
+    }
+
+    /// Returns the number of covered integers within `lo..=hi`.
+    #[inline(always)]
+    pub fn count_in(&self, lo: T, hi: T) -> u128 {
+        if lo > hi {
+            return 0;
+        }
+        let below = if lo == T::MIN {
+            0
+        } else {
+            self.covered_up_to(lo.pred())
+        };
+        self.covered_up_to(hi) - below
+    }
+
+    /// Returns the indices (in construction order) of the original ranges which contain `x`.
+    pub fn covering(&self, x: T) -> impl Iterator<Item = usize> + '_ {
+        let group = match self.span_index(x) {
+            Some(i) => &self.originals[self.group_start[i]..self.group_start[i + 1]],
+            None => &[],
+        };
+        group
+            .iter()
+            .take_while(move |&&(lo, _, _)| lo <= x)
+            .filter(move |&&(_, hi, _)| hi >= x)
+            .map(|&(_, _, i)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_coalesces() {
+        let mut set = RangeSet::new();
+        set.insert(10, 14);
+        set.insert(3, 5);
+        set.insert(16, 20);
+        set.insert(18, 12);
+        set.insert(6, 6);
+        assert_eq!(set.spans(), &[(3, 6), (10, 20)]);
+        assert_eq!(set.len(), 15);
+        assert!(set.contains(6) && set.contains(10) && !set.contains(7));
+        assert_eq!(
+            set,
+            RangeSet::from_range_lines("3-5\n10-14\n16-20\n12-18\n6-6\n")
+        );
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet<i64> = [(0, 10), (20, 30)].into_iter().collect();
+        let b: RangeSet<i64> = [(5, 25)].into_iter().collect();
+        assert_eq!(a.union(&b).spans(), &[(0, 30)]);
+        assert_eq!(a.intersection(&b).spans(), &[(5, 10), (20, 25)]);
+        assert_eq!(a.difference(&b).spans(), &[(0, 4), (26, 30)]);
+        assert_eq!(
+            a.complement(-5, 35).spans(),
+            &[(-5, -1), (11, 19), (31, 35)]
+        );
+        assert_eq!(
+            b.complement(i64::MIN, i64::MAX).len(),
+            u64::MAX as u128 - 20
+        );
+    }
+
+    #[test]
+    fn test_range_index() {
+        let index = RangeIndex::<u64>::from_range_lines("3-5\n10-14\n16-20\n12-18\n");
+        assert_eq!(index.set().spans(), &[(3, 5), (10, 20)]);
+        assert_eq!(index.count_contained([1, 5, 8, 11, 17, 32]), 3);
+        assert_eq!(index.count_in(0, 100), 14);
+        assert_eq!(index.count_in(4, 12), 5);
+        assert_eq!(index.count_in(6, 9), 0);
+
+        let mut covering: Vec<usize> = index.covering(13).collect();
+        covering.sort_unstable();
+        assert_eq!(covering, [1, 3]);
+        assert_eq!(index.covering(9).count(), 0);
+
+        for x in 0..25 {
+            assert_eq!(index.contains(x), index.set().contains(x), "x = {x}");
+        }
+    }
+}
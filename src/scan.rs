@@ -0,0 +1,319 @@
+use std::fmt;
+
+/// Parses a line against a `format!`-like pattern, returning a `Result` of a tuple of typed
+/// captures. Each `{}` captures up to the next literal; `{sep}` captures a repeated group split on
+/// `sep` into a `Vec` (a whitespace separator splits on any run of whitespace, which is also the
+/// default for `{}`). Use `{{` and `}}` for literal braces. The pattern must be a string literal,
+/// and is parsed once per call site.
+///
+/// ```
+/// use aoc2025::scan;
+///
+/// let (w, h, counts) = scan!("12x5: 1 0 1", "{}x{}: {}" => usize, usize, Vec<usize>).unwrap();
+/// assert_eq!((w, h, counts), (12, 5, vec![1, 0, 1]));
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:literal => $($t:ty),+ $(,)?) => {{
+        static PATTERN: ::std::sync::OnceLock<$crate::scan::Pattern> = ::std::sync::OnceLock::new();
+        let pattern = PATTERN.get_or_init(|| $crate::scan::Pattern::new($pattern));
+        $crate::scan::Scanner::new($input, pattern).and_then(|mut scanner| {
+            let captures = ($(scanner.capture::<$t>()?,)+);
+            scanner.finish().map(|_| captures)
+        })
+    }};
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScanError {
+    /// A literal in the pattern was not found where expected.
+    LiteralMismatch {
+        literal: String,
+        position: usize,
+        found: String,
+    },
+    /// Text remained after the final literal of the pattern.
+    TrailingInput { remaining: String },
+    /// A capture could not be converted into the requested type.
+    InvalidValue {
+        capture: usize,
+        text: String,
+        type_name: &'static str,
+    },
+    /// The pattern and the number of requested types disagree.
+    CaptureCount { pattern: usize, requested: usize },
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LiteralMismatch {
+                literal,
+                position,
+                found,
+            } => write!(
+                f,
+                "expected literal {literal:?} at byte {position}, found {found:?}"
+            ),
+            Self::TrailingInput { remaining } => {
+                write!(f, "unexpected trailing input {remaining:?}")
+            }
+            Self::InvalidValue {
+                capture,
+                text,
+                type_name,
+            } => write!(f, "capture {capture} ({text:?}) is not a valid {type_name}"),
+            Self::CaptureCount { pattern, requested } => write!(
+                f,
+                "pattern has {pattern} captures but {requested} types were requested"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// Types which can be produced from a single `scan!` capture.
+pub trait Scan<'a>: Sized {
+    /// Converts `text`, using `sep` as the separator for repeated groups.
+    fn scan(text: &'a str, sep: &str) -> Option<Self>;
+}
+
+macro_rules! impl_scan_from_str {
+    ($($t:ty),*) => {$(
+        impl<'a> Scan<'a> for $t {
+            #[inline(always)]
+            fn scan(text: &'a str, _sep: &str) -> Option<Self> {
+                text.trim().parse().ok()
+            }
+        }
+    )*};
+}
+
+impl_scan_from_str!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+impl_scan_from_str!(f32, f64, bool, char, String);
+
+impl<'a> Scan<'a> for &'a str {
+    #[inline(always)]
+    fn scan(text: &'a str, _sep: &str) -> Option<Self> {
+        Some(text.trim())
+    }
+}
+
+impl<'a, T: Scan<'a>> Scan<'a> for Vec<T> {
+    fn scan(text: &'a str, sep: &str) -> Option<Self> {
+        if sep.trim().is_empty() {
+            text.split_ascii_whitespace()
+                .map(|item| T::scan(item, ""))
+                .collect()
+        } else {
+            text.split(sep)
+                .filter(|item| !item.trim().is_empty())
+                .map(|item| T::scan(item, ""))
+                .collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Segment {
+    Literal(String),
+    Capture(String),
+}
+
+/// A parsed [`scan!`] pattern.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Pattern {
+    segments: Vec<Segment>,
+    captures: usize,
+}
+
+impl Pattern {
+    /// Splits a pattern into its literal and capture segments.
+    pub fn new(pattern: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = pattern.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let sep: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    assert!(
+                        !matches!(segments.last(), Some(Segment::Capture(_))),
+                        "Pattern {pattern:?} has adjacent captures"
+                    );
+                    segments.push(Segment::Capture(sep));
+                }
+                '}' => panic!("Pattern {pattern:?} has an unmatched '}}'"),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        let captures = segments
+            .iter()
+            .filter(|s| matches!(s, Segment::Capture(_)))
+            .count();
+        Self { segments, captures }
+    }
+}
+
+/// Matches an input against a pattern, one capture at a time, without allocating. Used by
+/// [`scan!`].
+pub struct Scanner<'a> {
+    input: &'a str,
+    pos: usize,
+    segments: &'a [Segment],
+    captures: usize,
+    next: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str, pattern: &'a Pattern) -> Result<Self, ScanError> {
+        let mut scanner = Self {
+            input,
+            pos: 0,
+            segments: &pattern.segments,
+            captures: pattern.captures,
+            next: 0,
+        };
+        scanner.match_literal()?;
+        Ok(scanner)
+    }
+
+    /// Consumes the literal at the front of the remaining pattern, if there is one.
+    fn match_literal(&mut self) -> Result<(), ScanError> {
+        if let [Segment::Literal(literal), rest @ ..] = self.segments {
+            let remaining = &self.input[self.pos..];
+            if !remaining.starts_with(literal.as_str()) {
+                return Err(ScanError::LiteralMismatch {
+                    literal: literal.clone(),
+                    position: self.pos,
+                    found: remaining.chars().take(literal.len().max(8)).collect(),
+                });
+            }
+            self.pos += literal.len();
+            self.segments = rest;
+        }
+        Ok(())
+    }
+
+    /// Converts the next capture into `T`.
+    pub fn capture<T: Scan<'a>>(&mut self) -> Result<T, ScanError> {
+        let capture = self.next;
+        self.next += 1;
+        let [Segment::Capture(sep), rest @ ..] = self.segments else {
+            return Err(ScanError::CaptureCount {
+                pattern: self.captures,
+                requested: self.next,
+            });
+        };
+
+        // A capture runs up to the next literal, or to the end of the input.
+        let end = match rest.first() {
+            Some(Segment::Literal(literal)) => {
+                match self.input[self.pos..].find(literal.as_str()) {
+                    Some(rel) => self.pos + rel,
+                    None => {
+                        return Err(ScanError::LiteralMismatch {
+                            literal: literal.clone(),
+                            position: self.input.len(),
+                            found: String::new(),
+                        });
+                    }
+                }
+            }
+            _ => self.input.len(),
+        };
+        let text = &self.input[self.pos..end];
+        self.pos = end;
+        self.segments = rest;
+        self.match_literal()?;
+
+        T::scan(text, sep).ok_or_else(|| ScanError::InvalidValue {
+            capture,
+            text: text.to_string(),
+            type_name: std::any::type_name::<T>(),
+        })
+    }
+
+    /// Checks that every capture in the pattern was consumed and nothing is left over.
+    pub fn finish(self) -> Result<(), ScanError> {
+        if self.next != self.captures {
+            return Err(ScanError::CaptureCount {
+                pattern: self.captures,
+                requested: self.next,
+            });
+        }
+        match self.input[self.pos..].trim() {
+            "" => Ok(()),
+            _ => Err(ScanError::TrailingInput {
+                remaining: self.input[self.pos..].to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScanError;
+
+    #[test]
+    fn test_scan() {
+        let line = "[.##.] (3) (1,3) (2) {3,5,4,7}";
+        let (lights, buttons, joltage) =
+            scan!(line, "[{}] {} {{{,}}}" => &str, Vec<&str>, Vec<i64>).unwrap();
+        assert_eq!(lights, ".##.");
+        assert_eq!(buttons, ["(3)", "(1,3)", "(2)"]);
+        assert_eq!(joltage, [3, 5, 4, 7]);
+        assert_eq!(scan!(buttons[1], "({,})" => Vec<usize>).unwrap().0, [1, 3]);
+
+        let (name, outputs) = scan!("aaa: you hhh", "{}: {}" => String, Vec<String>).unwrap();
+        assert_eq!((name.as_str(), outputs.len()), ("aaa", 2));
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        assert_eq!(
+            scan!("12y5: 1", "{}x{}: {}" => usize, usize, Vec<usize>).unwrap_err(),
+            ScanError::LiteralMismatch {
+                literal: "x".to_string(),
+                position: 7,
+                found: String::new(),
+            }
+        );
+        assert!(matches!(
+            scan!("12x5: a", "{}x{}: {}" => usize, usize, Vec<usize>),
+            Err(ScanError::InvalidValue { capture: 2, .. })
+        ));
+        assert!(matches!(
+            scan!("12x5", "{}x{}" => usize),
+            Err(ScanError::CaptureCount {
+                pattern: 2,
+                requested: 1
+            })
+        ));
+        assert_eq!(
+            scan!("1-2 extra", "{}-{} " => u8, u8)
+                .unwrap_err()
+                .to_string(),
+            "unexpected trailing input \"extra\""
+        );
+    }
+}
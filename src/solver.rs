@@ -0,0 +1,184 @@
+use crate::ilp::{Solution, Unsupported, min_sum_solution};
+use std::env;
+
+/// Environment variable naming the min-sum backend to use, or "all" to run every compiled-in one.
+pub const SOLVER_VAR: &str = "AOC_SOLVER";
+
+/// A backend for "minimise `Σx` subject to `a x = b` with `x` a vector of natural numbers".
+pub trait MinSumSolver: Sync {
+    /// The name which selects this backend through [`SOLVER_VAR`].
+    fn name(&self) -> &'static str;
+
+    /// Solves the system, or reports that it is outside what this backend handles.
+    fn solve(&self, a: &[Vec<i64>], b: &[i64]) -> Result<Solution, Unsupported>;
+}
+
+/// Exact elimination followed by enumeration of the free variables. Always available.
+pub struct Enumeration;
+
+impl MinSumSolver for Enumeration {
+    fn name(&self) -> &'static str {
+        "enumerate"
+    }
+
+    #[inline(always)]
+    fn solve(&self, a: &[Vec<i64>], b: &[i64]) -> Result<Solution, Unsupported> {
+        Ok(min_sum_solution(a, b))
+    }
+}
+
+/// In-crate branch and bound over the variables themselves, for non-negative coefficients only.
+#[cfg(feature = "branch-and-bound")]
+pub struct BranchAndBound;
+
+#[cfg(feature = "branch-and-bound")]
+impl MinSumSolver for BranchAndBound {
+    fn name(&self) -> &'static str {
+        "branch-and-bound"
+    }
+
+    #[inline(always)]
+    fn solve(&self, a: &[Vec<i64>], b: &[i64]) -> Result<Solution, Unsupported> {
+        crate::ilp::min_sum_branch_and_bound(a, b)
+    }
+}
+
+/// The pure Rust `microlp` solver through `good_lp`. It works in floating point, so its values
+/// are rounded and checked against the system, and anything which fails is left to the other
+/// backends.
+#[cfg(feature = "microlp")]
+pub struct Microlp;
+
+#[cfg(feature = "microlp")]
+impl MinSumSolver for Microlp {
+    fn name(&self) -> &'static str {
+        "microlp"
+    }
+
+    fn solve(&self, a: &[Vec<i64>], b: &[i64]) -> Result<Solution, Unsupported> {
+        use crate::ilp::{certify_infeasible, is_natural_solution};
+        use good_lp::{
+            Expression, ResolutionError, Solution as _, SolverModel, constraint, microlp, variable,
+            variables,
+        };
+
+        let cols = a.first().map_or(0, Vec::len);
+        let mut vars = variables!();
+        let x: Vec<_> = (0..cols)
+            .map(|_| vars.add(variable().min(0).integer()))
+            .collect();
+
+        let mut problem = vars.minimise(x.iter().sum::<Expression>()).using(microlp);
+        for (row, &target) in a.iter().zip(b) {
+            let expr: Expression = row.iter().zip(&x).map(|(&c, &v)| c as f64 * v).sum();
+            problem = problem.with(constraint!(expr == target as f64));
+        }
+
+        match problem.solve() {
+            Ok(solution) => {
+                let x: Vec<i64> = x
+                    .iter()
+                    .map(|&v| solution.value(v).round() as i64)
+                    .collect();
+                match is_natural_solution(a, b, &x) {
+                    true => Ok(Solution::Optimal(x)),
+                    false => Err(Unsupported("rounding lost precision")),
+                }
+            }
+            Err(ResolutionError::Infeasible) => Ok(Solution::Infeasible(certify_infeasible(a, b))),
+            Err(ResolutionError::Unbounded) => unreachable!("Σx is bounded below by zero"),
+            Err(_) => Err(Unsupported("microlp failed")),
+        }
+    }
+}
+
+/// Returns every compiled-in backend, the default first.
+pub fn backends() -> Vec<&'static dyn MinSumSolver> {
+    #[allow(unused_mut)]
+    let mut backends: Vec<&'static dyn MinSumSolver> = vec![&Enumeration];
+    #[cfg(feature = "branch-and-bound")]
+    backends.push(&BranchAndBound);
+    #[cfg(feature = "microlp")]
+    backends.push(&Microlp);
+    backends
+}
+
+/// Solves with `backend`, falling through the other compiled-in backends in order if it doesn't
+/// support the system. The default backend supports everything, so there is always an answer.
+pub fn solve_with_fallback(backend: &dyn MinSumSolver, a: &[Vec<i64>], b: &[i64]) -> Solution {
+    let others = backends()
+        .into_iter()
+        .filter(|other| other.name() != backend.name());
+    std::iter::once(backend)
+        .chain(others)
+        .find_map(|backend| backend.solve(a, b).ok())
+        .expect("The default backend supports every system")
+}
+
+/// Returns the backends chosen by [`SOLVER_VAR`]: the named one, every one for "all", or the
+/// default if it is unset. Panics on an unknown name.
+pub fn selected_backends() -> Vec<&'static dyn MinSumSolver> {
+    let backends = backends();
+    match env::var(SOLVER_VAR).as_deref() {
+        Err(_) => vec![backends[0]],
+        Ok("all") => backends,
+        Ok(name) => {
+            let names: Vec<_> = backends.iter().map(|b| b.name()).collect();
+            let backend = backends.iter().find(|b| b.name() == name);
+            vec![*backend.unwrap_or_else(|| {
+                panic!("Unknown solver {name:?}, expected one of {names:?} or \"all\"")
+            })]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ilp::Infeasibility;
+
+    #[test]
+    fn test_backends_agree() {
+        let a = [
+            vec![0, 0, 0, 0, 1, 1],
+            vec![0, 1, 0, 0, 0, 1],
+            vec![0, 0, 1, 1, 1, 0],
+            vec![1, 1, 0, 1, 0, 0],
+        ];
+        let b = [3, 5, 4, 7];
+
+        for backend in backends() {
+            let Ok(Solution::Optimal(x)) = backend.solve(&a, &b) else {
+                panic!("{} found no solution", backend.name());
+            };
+            assert_eq!(x.iter().sum::<i64>(), 10, "{}", backend.name());
+            assert_eq!(
+                backend.solve(&[vec![2]], &[3]),
+                Ok(Solution::Infeasible(Infeasibility::NoNaturalSolution)),
+                "{}",
+                backend.name()
+            );
+            assert_eq!(
+                backend.solve(&[vec![0, 1]], &[1]),
+                Ok(Solution::Optimal(vec![0, 1])),
+                "{}",
+                backend.name()
+            );
+
+            // Negative coefficients are beyond branch and bound, but every backend gets an answer.
+            let (a, b) = ([vec![1, -1], vec![0, 1]], [2, 3]);
+            assert_eq!(
+                solve_with_fallback(backend, &a, &b),
+                Solution::Optimal(vec![5, 3]),
+                "{}",
+                backend.name()
+            );
+        }
+
+        #[cfg(feature = "branch-and-bound")]
+        assert_eq!(
+            BranchAndBound.solve(&[vec![1, -1]], &[0]),
+            Err(Unsupported("negative coefficients"))
+        );
+    }
+}